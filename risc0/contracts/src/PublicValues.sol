@@ -0,0 +1,70 @@
+// SPDX-License-Identifier: MIT
+// Generated from `prover/src/public_values.sol` by `cargo run -p prover --bin gen-solidity`,
+// do not edit.
+pragma solidity ^0.8.20;
+
+/// The public values encoded as a struct that can be easily deserialized inside Solidity.
+struct PublicValuesStruct {
+    // The mode of the guest that committed the values, see `Mode::tag`.
+    uint8 mode;
+    uint256 input_hash;
+    uint256 block_id;
+    uint256 parent_block_id;
+    uint32 block_height;
+    uint32 consensus_parameters_version;
+    uint256 consensus_parameters_hash;
+    bytes32[] processed_relayed_transactions;
+    bytes32[] failed_relayed_transactions;
+    bytes32 message_outbox_root;
+    bytes32[] message_ids;
+}
+
+/// The public values of the single transaction execution.
+struct TransactionPublicValuesStruct {
+    // The mode of the guest that committed the values, see `Mode::tag`.
+    uint8 mode;
+    uint256 input_hash;
+    bytes32 tx_id;
+    bytes32 receipts_root;
+    uint8 status;
+    uint32 consensus_parameters_version;
+    uint256 consensus_parameters_hash;
+}
+
+/// The public values of the aggregated proof of consecutive blocks.
+struct AggregatedPublicValuesStruct {
+    bytes32 program_id;
+    uint256 parent_block_id;
+    uint256 block_id;
+    uint32 first_block_height;
+    uint32 last_block_height;
+    bytes32[] block_ids;
+    bytes32[] input_hashes;
+    bytes32[] message_outbox_roots;
+}
+
+library PublicValues {
+    function decodePublicValues(bytes memory publicValues)
+        internal
+        pure
+        returns (PublicValuesStruct memory)
+    {
+        return abi.decode(publicValues, (PublicValuesStruct));
+    }
+
+    function decodeTransactionPublicValues(bytes memory publicValues)
+        internal
+        pure
+        returns (TransactionPublicValuesStruct memory)
+    {
+        return abi.decode(publicValues, (TransactionPublicValuesStruct));
+    }
+
+    function decodeAggregatedPublicValues(bytes memory publicValues)
+        internal
+        pure
+        returns (AggregatedPublicValuesStruct memory)
+    {
+        return abi.decode(publicValues, (AggregatedPublicValuesStruct));
+    }
+}
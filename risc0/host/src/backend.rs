@@ -0,0 +1,35 @@
+//! The RISC Zero backend of the prover daemon.
+use input_provider::daemon::{BlockProof, ProvingBackend};
+use methods::{PROVE_FUEL_ELF, PROVE_FUEL_ID};
+use prover::Mode;
+use risc0_zkvm::{default_prover, sha::Digest, ExecutorEnv};
+
+/// Proves the blocks with the default prover, the proof is the bincode encoded `Receipt`.
+pub struct Risc0Backend;
+
+impl ProvingBackend for Risc0Backend {
+    fn name(&self) -> &'static str {
+        "risc0"
+    }
+
+    fn vkey(&self) -> Option<String> {
+        Some(format!("0x{}", Digest::from(PROVE_FUEL_ID)))
+    }
+
+    fn prove(&self, input: &[u8]) -> anyhow::Result<BlockProof> {
+        let env = ExecutorEnv::builder()
+            .write(&Mode::Validation)?
+            .write(&input)?
+            .build()?;
+
+        let prove_info = default_prover().prove(env, PROVE_FUEL_ELF)?;
+        let receipt = prove_info.receipt;
+        receipt.verify(PROVE_FUEL_ID)?;
+
+        Ok(BlockProof {
+            public_values: receipt.journal.bytes.clone(),
+            proof: bincode::serialize(&receipt)?,
+            cycles: Some(prove_info.stats.total_cycles),
+        })
+    }
+}
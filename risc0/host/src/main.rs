@@ -11,11 +11,255 @@
 //!
 //! The `RISC0_DEV_MODE=1` flag enables development mode, and `RUST_LOG=info` configures logging
 //! for better visibility.
+//!
+//! To prove a block under the rules of another chain, pass its snapshot directory:
+//! ```shell
+//! RISC0_DEV_MODE=1 RUST_LOG=info cargo run --release -- --snapshot <PATH>
+//! ```
+//!
+//! The state of the snapshot can be overridden with `--state-config <FILE>`, and the
+//! transactions are paid by the wallet with `--wallet-secret <HEX>`, which must own coins
+//! in the state of the chain.
+//!
+//! The `--workload` argument selects the kind of transactions in the block,
+//! for example `contract-call`, `predicate-spend` or `blob`.
+//!
+//! With `--production`, the guest produces the block from its header and transactions
+//! instead of validating the block produced by the node.
+//!
+//! With `--transaction`, the guest executes a transfer from the wallet in the context of the
+//! next block without including it into a block, and commits the transaction id, receipts root
+//! and status:
+//! ```shell
+//! RISC0_DEV_MODE=1 RUST_LOG=info cargo run --release -- --transaction
+//! ```
+//!
+//! With `--aggregate <N>`, the host proves `N` consecutive blocks and aggregates their proofs
+//! into one proof with the aggregation guest.
+//!
+//! With `--groth16`, the composite receipt is compressed into a succinct receipt and wrapped
+//! into a Groth16 proof that can be verified on-chain. The seal encoded for the RISC Zero
+//! verifier contract and the ABI encoded journal are saved as a fixture into `--fixtures-dir`:
+//! ```shell
+//! RUST_LOG=info cargo run --release -- --groth16 --fixtures-dir <FIXTURES_DIR>
+//! ```
+//!
+//! With `--segment-workers <N>`, the guest is executed once to split it into segments, the
+//! segments are proved on `N` threads and joined into one succinct receipt, see [`segments`]:
+//! ```shell
+//! RUST_LOG=info cargo run --release -- --segment-workers 8
+//! ```
+//!
+//! With `--profile`, the guest is executed without proving, and the host prints the cycles
+//! of every phase of proving, see [`input_provider::profile`]:
+//! ```shell
+//! RUST_LOG=info cargo run --release -- --profile --workload contract-call
+//! ```
+//!
+//! To find the hot functions of the FuelVM and the executor, write the pprof profile of the
+//! execution of the guest on the input saved with `--output`, and open it as a flamegraph:
+//! ```shell
+//! cargo run --release -- pprof <DIR>/input.bin --output profile.pb
+//! go tool pprof -http=127.0.0.1:8000 profile.pb
+//! ```
+//!
+//! With `--output <DIR>`, the proof is saved with its input and public values,
+//! see [`proof_files`] for the format. The saved proof can be verified later with:
+//! ```shell
+//! cargo run --release -- verify <DIR>
+//! ```
+//!
+//! To run the prover daemon that proves every new block of the node, saving the node database
+//! and the proved blocks into the directory:
+//! ```shell
+//! RUST_LOG=info cargo run --release -- daemon <DIR> --workload script
+//! ```
+//! The `--workload` is sent every `--interval` seconds to produce blocks, otherwise the blocks
+//! are produced by the transactions sent to the node. With `--api <ADDR>`, the daemon serves
+//! the HTTP API to request and fetch the proofs, see [`input_provider::api`].
+//!
+//! To prove the blocks on many machines, start the daemon with `--coordinator <ADDR>` and
+//! `--parallel-jobs <N>`, and run the workers that connect to it:
+//! ```shell
+//! RUST_LOG=info cargo run --release -- worker <ADDR>
+//! ```
+//!
+//! The saved Groth16 proof can be exported as a fixture for the Solidity tests with:
+//! ```shell
+//! cargo run --release -- fixture <DIR> --fixtures-dir <FIXTURES_DIR>
+//! ```
 use alloy_sol_types::SolType;
-use input_provider::start_node_with_transaction_and_produce_prover_input;
-use methods::{PROVE_FUEL_ELF, PROVE_FUEL_ID};
-use prover::PublicValuesStruct;
-use risc0_zkvm::{default_prover, ExecutorEnv};
+use backend::Risc0Backend;
+use clap::{Parser, Subcommand};
+use input_provider::{
+    api,
+    coordinator::Coordinator,
+    daemon::{DaemonConfig, ProverDaemon, ProvingBackend},
+    fixture::{ProofFixture, Zkvm},
+    produce_prover_production_input_for_block,
+    profile::{CycleReport, CYCLE_PROFILE_ENV},
+    start_node, start_node_and_produce_prover_transaction_input,
+    start_node_with_workload_and_produce_prover_input,
+    start_node_with_workloads_and_produce_prover_inputs, transaction_sender,
+    worker::ProvingWorker,
+    workload::{TransactionSender, Workload},
+    NodeConfig,
+};
+use manifest::Manifest;
+use methods::{AGGREGATE_FUEL_ELF, AGGREGATE_FUEL_ID, PROVE_FUEL_ELF, PROVE_FUEL_ID};
+use proof_files::ProofFiles;
+use prover::{
+    AggregatedPublicValuesStruct, Mode, PublicValuesStruct, TransactionPublicValuesStruct,
+};
+use risc0_zkvm::{default_prover, sha::Digest, ExecutorEnv, ExecutorImpl, ProverOpts, Receipt};
+use std::{
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+mod backend;
+mod manifest;
+mod proof_files;
+mod segments;
+
+/// The arguments for the command.
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    #[clap(subcommand)]
+    command: Option<Command>,
+
+    /// The chain snapshot directory in the `fuel-core` format.
+    /// The local testnet is used by default.
+    #[clap(long, env = "FUEL_SNAPSHOT")]
+    snapshot: Option<PathBuf>,
+
+    /// The state config JSON file that overrides the state of the chain,
+    /// in the format of the `state_config.json` of the snapshot.
+    #[clap(long)]
+    state_config: Option<PathBuf>,
+
+    /// The hex encoded secret key of the wallet that pays for the transactions.
+    /// It must own coins in the state of the chain. A local testnet wallet by default.
+    #[clap(long, env = "FUEL_WALLET_SECRET")]
+    wallet_secret: Option<String>,
+
+    /// The kind of transactions included into the proven block.
+    #[clap(long, default_value_t = Workload::Script)]
+    workload: Workload,
+
+    /// Produce blocks with fixed timestamps to get a reproducible input.
+    #[clap(long)]
+    deterministic: bool,
+
+    /// Prove the production of the block from its header and transactions
+    /// instead of the validation of the block.
+    #[clap(long)]
+    production: bool,
+
+    /// Prove the execution of one transaction instead of the block.
+    #[clap(long)]
+    transaction: bool,
+
+    /// Prove this number of consecutive blocks and aggregate their proofs.
+    #[clap(long)]
+    aggregate: Option<usize>,
+
+    /// The directory to save the proof with its input and public values.
+    #[clap(long)]
+    output: Option<PathBuf>,
+
+    /// Wrap the proof into a Groth16 proof that can be verified on-chain.
+    #[clap(long)]
+    groth16: bool,
+
+    /// The directory to save the fixture of the Groth16 proof.
+    #[clap(long, default_value = "fixtures")]
+    fixtures_dir: PathBuf,
+
+    /// Prove the segments in parallel on this number of threads and join them
+    /// into one succinct receipt instead of using the default prover.
+    #[clap(long)]
+    segment_workers: Option<usize>,
+
+    /// Execute the guest without proving and print the cycles of the phases.
+    #[clap(long)]
+    profile: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Verifies the proof saved with `--output`.
+    Verify {
+        /// The directory with the saved proof.
+        proof: PathBuf,
+
+        /// The image id of the guest in hex. The image id of the embedded guest by default.
+        #[clap(long)]
+        image_id: Option<String>,
+    },
+    /// Exports the Groth16 proof saved with `--output` as a fixture for the Solidity tests.
+    Fixture {
+        /// The directory with the saved proof.
+        proof: PathBuf,
+
+        /// The directory to save the fixture.
+        #[clap(long, default_value = "fixtures")]
+        fixtures_dir: PathBuf,
+    },
+    /// Executes the guest on the saved input and writes the pprof profile of the execution.
+    Pprof {
+        /// The bincode encoded prover input, for example `input.bin` saved with `--output`.
+        input: PathBuf,
+
+        /// The file to write the profile to.
+        #[clap(long, env = "RISC0_PPROF_OUT", default_value = "profile.pb")]
+        output: PathBuf,
+
+        /// The input is for the production of the block instead of the validation.
+        #[clap(long)]
+        production: bool,
+    },
+    /// Runs the prover daemon that proves every new block of the node.
+    Daemon(DaemonArgs),
+    /// Runs the worker that proves the blocks for the daemon started with `--coordinator`.
+    Worker {
+        /// The address of the coordinator, for example `10.0.0.1:9000`.
+        coordinator: String,
+    },
+}
+
+#[derive(clap::Args, Debug)]
+struct DaemonArgs {
+    /// The directory for the node database and the proved blocks.
+    dir: PathBuf,
+
+    /// The number of attempts to prove the block before giving up.
+    #[clap(long, default_value_t = 3)]
+    max_attempts: u32,
+
+    /// The number of blocks proved at once.
+    #[clap(long, default_value_t = 1)]
+    parallel_jobs: usize,
+
+    /// The kind of transactions sent to the node to produce blocks.
+    #[clap(long)]
+    workload: Option<Workload>,
+
+    /// The interval in seconds between the workloads.
+    #[clap(long, default_value_t = 10)]
+    interval: u64,
+
+    /// The address to serve the HTTP API on, for example `127.0.0.1:8080`.
+    #[clap(long)]
+    api: Option<SocketAddr>,
+
+    /// The address to listen for the workers on, for example `0.0.0.0:9000`.
+    /// The blocks are proved by the workers instead of the local prover.
+    #[clap(long)]
+    coordinator: Option<SocketAddr>,
+}
 
 #[tokio::main]
 async fn main() {
@@ -23,24 +267,134 @@ async fn main() {
         .with_env_filter(tracing_subscriber::filter::EnvFilter::from_default_env())
         .init();
 
-    let service = start_node_with_transaction_and_produce_prover_input()
+    let args = Args::parse();
+
+    match &args.command {
+        Some(Command::Verify { proof, image_id }) => {
+            verify(proof, image_id.as_deref()).unwrap();
+            return;
+        }
+        Some(Command::Fixture {
+            proof,
+            fixtures_dir,
+        }) => {
+            export_fixture(proof, fixtures_dir).unwrap();
+            return;
+        }
+        Some(Command::Pprof {
+            input,
+            output,
+            production,
+        }) => {
+            let mode = if *production {
+                Mode::Production
+            } else {
+                Mode::Validation
+            };
+            write_pprof(input, output, mode).unwrap();
+            return;
+        }
+        Some(Command::Worker { coordinator }) => {
+            ProvingWorker::new(Risc0Backend)
+                .run(coordinator.as_str())
+                .await
+                .unwrap();
+            println!("The coordinator is stopped");
+            return;
+        }
+        Some(Command::Daemon(_)) | None => {}
+    }
+
+    let mut node_config = NodeConfig::default();
+    if let Some(snapshot) = args.snapshot {
+        node_config = node_config.with_snapshot(snapshot);
+    }
+    if let Some(state_config) = &args.state_config {
+        node_config = node_config.with_state_config_file(state_config).unwrap();
+    }
+    if let Some(wallet_secret) = &args.wallet_secret {
+        node_config = node_config.with_wallet_secret_hex(wallet_secret).unwrap();
+    }
+    if args.deterministic {
+        node_config = node_config.with_deterministic_block_production();
+    }
+
+    if let Some(Command::Daemon(daemon_args)) = args.command {
+        run_daemon(node_config, daemon_args).await.unwrap();
+        return;
+    }
+
+    if args.transaction {
+        prove_transaction(node_config, args.profile).await;
+        return;
+    }
+
+    if let Some(blocks) = args.aggregate {
+        aggregate_blocks(node_config, args.workload, blocks).await;
+        return;
+    }
+
+    let service = start_node_with_workload_and_produce_prover_input(node_config, args.workload)
         .await
         .unwrap();
 
     let block_id: [u8; 32] = service.input.block.header().id().into();
 
-    let input: Vec<u8> =
-        bincode::serialize(&service.input).expect("Failed to serialize service input");
+    let (mode, input) = if args.production {
+        let production_input =
+            produce_prover_production_input_for_block(&service.fuel_node, &service.input.block)
+                .unwrap();
+        let input =
+            bincode::serialize(&production_input).expect("Failed to serialize production input");
+        (Mode::Production, input)
+    } else {
+        let input = bincode::serialize(&service.input).expect("Failed to serialize service input");
+        (Mode::Validation, input)
+    };
+
+    if args.profile {
+        let report = profile(mode, &input).unwrap();
+        println!("{report}");
+        return;
+    }
 
     let env = ExecutorEnv::builder()
+        .write(&mode)
+        .unwrap()
         .write(&input)
         .unwrap()
         .build()
         .unwrap();
 
+    // The Groth16 proof is generated from the succinct receipt of the composite one.
+    let opts = if args.groth16 {
+        ProverOpts::groth16()
+    } else {
+        ProverOpts::default()
+    };
+
     let prover = default_prover();
-    let prove_info = prover.prove(env, PROVE_FUEL_ELF).unwrap();
-    let output: Vec<u8> = prove_info.receipt.journal.decode().unwrap();
+    let receipt = match args.segment_workers {
+        Some(workers) => {
+            let proof = segments::prove(env, PROVE_FUEL_ELF, workers).unwrap();
+            println!(
+                "Proved {} segments of {} cycles on {workers} threads",
+                proof.segments, proof.total_cycles
+            );
+            if args.groth16 {
+                prover.compress(&opts, &proof.receipt).unwrap()
+            } else {
+                proof.receipt
+            }
+        }
+        None => {
+            prover
+                .prove_with_opts(env, PROVE_FUEL_ELF, &opts)
+                .unwrap()
+                .receipt
+        }
+    };
+    let output = receipt.journal.bytes.clone();
 
     let decoded_output = PublicValuesStruct::abi_decode(&output, true).unwrap();
 
@@ -48,11 +402,370 @@ async fn main() {
 
     println!("Proof block id: {:?}", decoded_output.block_id);
     println!("Proof input hash: {:?}", decoded_output.input_hash);
+    println!(
+        "Proof consensus parameters version: {}",
+        decoded_output.consensus_parameters_version
+    );
+    println!(
+        "Proof consensus parameters hash: {:?}",
+        decoded_output.consensus_parameters_hash
+    );
+    println!(
+        "Proof processed relayed transactions: {:?}",
+        decoded_output.processed_relayed_transactions
+    );
+    println!(
+        "Proof failed relayed transactions: {:?}",
+        decoded_output.failed_relayed_transactions
+    );
+    println!(
+        "Proof message outbox root: {:?}",
+        decoded_output.message_outbox_root
+    );
 
-    prove_info
+    receipt
+        .verify(PROVE_FUEL_ID)
+        .expect("Proof verification failed.");
+
+    println!("Successfully verified proof!");
+
+    if args.groth16 {
+        let fixture =
+            groth16_fixture(&receipt, &Digest::from(PROVE_FUEL_ID).to_string(), &output).unwrap();
+        println!("Image id: {}", fixture.vkey);
+        println!("Journal: {}", fixture.public_values);
+        println!("Seal: {}", fixture.proof);
+
+        let path = fixture.save(&args.fixtures_dir).unwrap();
+        println!("Saved the fixture to {}", path.display());
+    }
+
+    if let Some(dir) = &args.output {
+        let proof_files = ProofFiles {
+            manifest: Manifest::current(),
+            receipt,
+            input,
+            public_values: output,
+        };
+        proof_files.save(dir).unwrap();
+        println!("Saved the proof to {}", dir.display());
+        println!("Image id: {}", Digest::from(PROVE_FUEL_ID));
+    }
+}
+
+/// Executes the guest with the profiling enabled and returns the cycles of its phases.
+fn profile(mode: Mode, input: &[u8]) -> anyhow::Result<CycleReport> {
+    let mut stdout = vec![];
+    let env = ExecutorEnv::builder()
+        .write(&mode)?
+        .write(&input)?
+        .env_var(CYCLE_PROFILE_ENV, "1")
+        .stdout(&mut stdout)
+        .build()?;
+    let session = ExecutorImpl::from_elf(env, PROVE_FUEL_ELF)?.run()?;
+    println!(
+        "Executed {} user cycles of {} cycles in {} segments",
+        session.user_cycles,
+        session.total_cycles,
+        session.segments.len()
+    );
+
+    CycleReport::parse(&String::from_utf8_lossy(&stdout), session.user_cycles)
+}
+
+/// Executes the guest on the `input` file in the `mode` and writes the pprof profile
+/// of the execution into the `output` file. The frames are symbolized from the guest ELF,
+/// so the profile shows the functions of the FuelVM and the executor.
+fn write_pprof(input: &Path, output: &Path, mode: Mode) -> anyhow::Result<()> {
+    let input = std::fs::read(input)?;
+    let env = ExecutorEnv::builder()
+        .write(&mode)?
+        .write(&input)?
+        .enable_profiler(output)
+        .build()?;
+    let session = ExecutorImpl::from_elf(env, PROVE_FUEL_ELF)?.run()?;
+
+    println!(
+        "Executed {} user cycles of {} cycles",
+        session.user_cycles, session.total_cycles
+    );
+    println!("Saved the profile to {}", output.display());
+    Ok(())
+}
+
+/// Verifies the proof saved in the `dir` against the `image_id`, the journal
+/// against the public values and the public values against the input.
+/// Proofs of other guest builds are rejected by their manifest.
+fn verify(dir: &Path, image_id: Option<&str>) -> anyhow::Result<()> {
+    let image_id = match image_id {
+        Some(image_id) => {
+            let bytes: [u8; 32] = hex::decode(image_id.trim_start_matches("0x"))?
+                .try_into()
+                .map_err(|_| anyhow::anyhow!("The image id must be 32 bytes"))?;
+            Digest::from_bytes(bytes)
+        }
+        None => Digest::from(PROVE_FUEL_ID),
+    };
+    let proof_files = ProofFiles::load(dir)?;
+
+    let expected_manifest = Manifest {
+        image_id: image_id.to_string(),
+        ..Manifest::current()
+    };
+    proof_files.manifest.check(&expected_manifest)?;
+
+    proof_files
         .receipt
+        .verify(image_id)
+        .map_err(|e| anyhow::anyhow!("Proof verification failed: {e}"))?;
+
+    anyhow::ensure!(
+        proof_files.receipt.journal.bytes == proof_files.public_values,
+        "The journal doesn't match the saved public values"
+    );
+
+    let public_values = PublicValuesStruct::abi_decode(&proof_files.public_values, true)?;
+    let input_hash: [u8; 32] = prover::input_hash(&proof_files.input).into();
+    anyhow::ensure!(
+        public_values.input_hash.to_be_bytes() == input_hash,
+        "The saved input doesn't match the committed input hash"
+    );
+
+    println!("Proof block id: {:?}", public_values.block_id);
+    println!("Proof block height: {}", public_values.block_height);
+    println!("Successfully verified proof!");
+    Ok(())
+}
+
+/// Runs the prover daemon for the node with the database in the `dir` until Ctrl-C.
+/// The proved blocks are saved into `<dir>/blocks`, and the `workload`,
+/// if any, is sent every `interval` to produce blocks.
+async fn run_daemon(node_config: NodeConfig, args: DaemonArgs) -> anyhow::Result<()> {
+    let node_config = match args.workload {
+        Some(workload) => workload.prepare(node_config)?,
+        None => node_config,
+    };
+    let fuel_node = start_node(&node_config, &args.dir.join("db")).await?;
+    println!("The node is listening on {}", fuel_node.bound_address);
+
+    let sender = match args.workload {
+        Some(workload) => Some((transaction_sender(&fuel_node, &node_config).await, workload)),
+        None => None,
+    };
+    let config = DaemonConfig::new(args.dir.join("blocks"))
+        .with_max_attempts(args.max_attempts)
+        .with_parallel_jobs(args.parallel_jobs);
+    match args.coordinator {
+        Some(address) => {
+            let coordinator = Coordinator::new(&Risc0Backend);
+            let listener = tokio::net::TcpListener::bind(address).await?;
+            tokio::spawn(coordinator.clone().serve(listener));
+            let daemon = ProverDaemon::new(&fuel_node, coordinator, config)?;
+            follow_chain(daemon, sender, &args).await
+        }
+        None => {
+            let daemon = ProverDaemon::new(&fuel_node, Risc0Backend, config)?;
+            follow_chain(daemon, sender, &args).await
+        }
+    }
+}
+
+/// Proves the new blocks with the `daemon` until Ctrl-C,
+/// while the `sender` sends its workload every interval.
+async fn follow_chain<B: ProvingBackend>(
+    daemon: ProverDaemon<'_, B>,
+    sender: Option<(TransactionSender, Workload)>,
+    args: &DaemonArgs,
+) -> anyhow::Result<()> {
+    let shutdown = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+
+    let send_workloads = async {
+        let Some((sender, workload)) = sender else {
+            return std::future::pending::<()>().await;
+        };
+        loop {
+            if let Err(e) = sender.send(workload).await {
+                eprintln!("Unable to send the workload: {e}");
+            }
+            tokio::time::sleep(Duration::from_secs(args.interval)).await;
+        }
+    };
+
+    let handle = daemon.handle();
+    let api_address = args.api;
+    let serve_api = async move {
+        let Some(address) = api_address else {
+            return std::future::pending::<anyhow::Result<()>>().await;
+        };
+        let listener = tokio::net::TcpListener::bind(address).await?;
+        api::serve(listener, handle).await
+    };
+
+    tokio::select! {
+        result = daemon.run(shutdown) => result,
+        _ = send_workloads => Ok(()),
+        result = serve_api => result,
+    }
+}
+
+/// Exports the Groth16 proof saved in the `dir` as a fixture into the `fixtures_dir`.
+fn export_fixture(dir: &Path, fixtures_dir: &Path) -> anyhow::Result<()> {
+    let proof_files = ProofFiles::load(dir)?;
+    let fixture = groth16_fixture(
+        &proof_files.receipt,
+        &proof_files.manifest.image_id,
+        &proof_files.public_values,
+    )?;
+    let path = fixture.save(fixtures_dir)?;
+    println!("Saved the fixture to {}", path.display());
+    Ok(())
+}
+
+/// Creates the fixture of the Groth16 `receipt` of the guest with the `image_id`
+/// that committed the `public_values`.
+fn groth16_fixture(
+    receipt: &Receipt,
+    image_id: &str,
+    public_values: &[u8],
+) -> anyhow::Result<ProofFixture> {
+    let groth16 = receipt
+        .inner
+        .groth16()
+        .map_err(|_| anyhow::anyhow!("Only Groth16 receipts can be verified on-chain"))?;
+
+    // The RISC Zero verifier router selects the verifier by the first 4 bytes
+    // of the verifier parameters digest prepended to the seal.
+    let seal = [
+        &groth16.verifier_parameters.as_bytes()[..4],
+        groth16.seal.as_slice(),
+    ]
+    .concat();
+
+    ProofFixture::new(
+        Zkvm::Risc0,
+        "groth16",
+        format!("0x{image_id}"),
+        public_values,
+        &seal,
+    )
+}
+
+/// Proves the transfer from the wallet in the context of the next block
+/// with the [`Mode::Transaction`]. With `profile`, the guest is executed without proving.
+async fn prove_transaction(node_config: NodeConfig, profile_only: bool) {
+    let service = start_node_and_produce_prover_transaction_input(node_config)
+        .await
+        .unwrap();
+    let input = bincode::serialize(&service.input).expect("Failed to serialize transaction input");
+
+    if profile_only {
+        let report = profile(Mode::Transaction, &input).unwrap();
+        println!("{report}");
+        return;
+    }
+
+    let env = ExecutorEnv::builder()
+        .write(&Mode::Transaction)
+        .unwrap()
+        .write(&input)
+        .unwrap()
+        .build()
+        .unwrap();
+    let receipt = default_prover()
+        .prove(env, PROVE_FUEL_ELF)
+        .unwrap()
+        .receipt;
+
+    let decoded_output =
+        TransactionPublicValuesStruct::abi_decode(&receipt.journal.bytes, true).unwrap();
+
+    assert_eq!(decoded_output.tx_id.0, *service.tx_id);
+
+    println!("Proof transaction id: {:?}", decoded_output.tx_id);
+    println!("Proof receipts root: {:?}", decoded_output.receipts_root);
+    println!("Proof transaction status: {}", decoded_output.status);
+
+    receipt
         .verify(PROVE_FUEL_ID)
         .expect("Proof verification failed.");
 
     println!("Successfully verified proof!");
 }
+
+/// Proves `blocks` consecutive blocks with the `workload` and aggregates their proofs.
+async fn aggregate_blocks(node_config: NodeConfig, workload: Workload, blocks: usize) {
+    let workloads = vec![workload; blocks];
+    let service = start_node_with_workloads_and_produce_prover_inputs(node_config, &workloads)
+        .await
+        .unwrap();
+
+    let prover = default_prover();
+
+    // The aggregation guest verifies block proofs, so they must be succinct.
+    let receipts = service
+        .inputs
+        .iter()
+        .map(|input| {
+            let input = bincode::serialize(input).expect("Failed to serialize service input");
+            let env = ExecutorEnv::builder()
+                .write(&Mode::Validation)
+                .unwrap()
+                .write(&input)
+                .unwrap()
+                .build()
+                .unwrap();
+            prover
+                .prove_with_opts(env, PROVE_FUEL_ELF, &ProverOpts::succinct())
+                .unwrap()
+                .receipt
+        })
+        .collect::<Vec<_>>();
+    println!("Proved {} blocks", receipts.len());
+
+    let journals = receipts
+        .iter()
+        .map(|receipt| receipt.journal.bytes.clone())
+        .collect::<Vec<_>>();
+
+    let mut env = ExecutorEnv::builder();
+    for receipt in receipts {
+        env.add_assumption(receipt);
+    }
+    let env = env
+        .write(&PROVE_FUEL_ID)
+        .unwrap()
+        .write(&journals)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let prove_info = prover.prove(env, AGGREGATE_FUEL_ELF).unwrap();
+    let output = prove_info.receipt.journal.bytes.clone();
+
+    let decoded_output = AggregatedPublicValuesStruct::abi_decode(&output, true).unwrap();
+
+    // The aggregation guest verifies the receipts against the image id from its input.
+    assert_eq!(
+        decoded_output.program_id.0,
+        prover::program_id(PROVE_FUEL_ID)
+    );
+
+    println!(
+        "Aggregated blocks: {}..={}",
+        decoded_output.first_block_height, decoded_output.last_block_height
+    );
+    println!(
+        "Aggregated parent block id: {:?}",
+        decoded_output.parent_block_id
+    );
+    println!("Aggregated block id: {:?}", decoded_output.block_id);
+
+    prove_info
+        .receipt
+        .verify(AGGREGATE_FUEL_ID)
+        .expect("Aggregated proof verification failed.");
+
+    println!("Successfully verified aggregated proof!");
+}
@@ -0,0 +1,52 @@
+//! The description of the guest build that generated the proof.
+//!
+//! The manifest is stored with every proof as `manifest.json`. The verifier compares it
+//! with the manifest of its own build and rejects proofs of other guests.
+use methods::{FUEL_CORE_VERSION, PROVER_VERSION, PROVE_FUEL_ID};
+use risc0_zkvm::sha::Digest;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    /// The image id of the guest in hex.
+    pub image_id: String,
+    /// The version of the `prover` crate. It's bumped when the public values or their encoding
+    /// change, so the proofs with the old journals are rejected before they are decoded.
+    pub prover_version: String,
+    /// The version of `fuel-core` used by the prover.
+    pub fuel_core_version: String,
+}
+
+impl Manifest {
+    /// Returns the manifest of the guest embedded into this binary.
+    pub fn current() -> Self {
+        Self {
+            image_id: Digest::from(PROVE_FUEL_ID).to_string(),
+            prover_version: PROVER_VERSION.to_string(),
+            fuel_core_version: FUEL_CORE_VERSION.to_string(),
+        }
+    }
+
+    /// Checks that the proof with this manifest was generated by the `expected` guest.
+    pub fn check(&self, expected: &Manifest) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            self.image_id == expected.image_id,
+            "The proof was generated by the guest with image id {}, but {} is expected",
+            self.image_id,
+            expected.image_id
+        );
+        anyhow::ensure!(
+            self.prover_version == expected.prover_version,
+            "The proof was generated with the prover {}, but {} is expected",
+            self.prover_version,
+            expected.prover_version
+        );
+        anyhow::ensure!(
+            self.fuel_core_version == expected.fuel_core_version,
+            "The proof was generated with fuel-core {}, but {} is expected",
+            self.fuel_core_version,
+            expected.fuel_core_version
+        );
+        Ok(())
+    }
+}
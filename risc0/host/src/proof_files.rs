@@ -0,0 +1,61 @@
+//! Persistence of proofs, so proving and verification can happen on different machines
+//! and at different times.
+//!
+//! The proof is stored in a directory with the files:
+//! - `receipt.bin` is the bincode encoded RISC Zero `Receipt`;
+//! - `input.bin` is the bincode encoded prover input the proof was generated for,
+//!   its hash is committed as the `input_hash`;
+//! - `public_values.bin` is the ABI encoded `PublicValuesStruct` committed to the journal;
+//! - `manifest.json` is the [`Manifest`] of the guest build that generated the proof.
+use crate::manifest::Manifest;
+use anyhow::Context;
+use risc0_zkvm::Receipt;
+use std::{fs, path::Path};
+
+const RECEIPT_FILE: &str = "receipt.bin";
+const INPUT_FILE: &str = "input.bin";
+const PUBLIC_VALUES_FILE: &str = "public_values.bin";
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// The proof with the input and the public values.
+pub struct ProofFiles {
+    pub manifest: Manifest,
+    pub receipt: Receipt,
+    pub input: Vec<u8>,
+    pub public_values: Vec<u8>,
+}
+
+impl ProofFiles {
+    /// Saves the proof into the `dir`, creating it if it doesn't exist.
+    pub fn save(&self, dir: &Path) -> anyhow::Result<()> {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Unable to create the directory {}", dir.display()))?;
+        fs::write(dir.join(RECEIPT_FILE), bincode::serialize(&self.receipt)?)?;
+        fs::write(dir.join(INPUT_FILE), &self.input)?;
+        fs::write(dir.join(PUBLIC_VALUES_FILE), &self.public_values)?;
+        fs::write(
+            dir.join(MANIFEST_FILE),
+            serde_json::to_string_pretty(&self.manifest)?,
+        )?;
+        Ok(())
+    }
+
+    /// Loads the proof saved with [`ProofFiles::save`] from the `dir`.
+    pub fn load(dir: &Path) -> anyhow::Result<Self> {
+        let read = |file: &str| {
+            let path = dir.join(file);
+            fs::read(&path).with_context(|| format!("Unable to read {}", path.display()))
+        };
+        let manifest = serde_json::from_slice(&read(MANIFEST_FILE)?)
+            .context("Unable to decode the manifest")?;
+        let receipt =
+            bincode::deserialize(&read(RECEIPT_FILE)?).context("Unable to decode the receipt")?;
+
+        Ok(Self {
+            manifest,
+            receipt,
+            input: read(INPUT_FILE)?,
+            public_values: read(PUBLIC_VALUES_FILE)?,
+        })
+    }
+}
@@ -0,0 +1,116 @@
+//! Proves the segments of the execution in parallel and joins them into one succinct receipt.
+//!
+//! The guest is executed once to split the execution into segments. The segments are proved
+//! and lifted on the worker threads, each with its own prover, and the lifted receipts are
+//! joined pairwise, level by level, also in parallel, until one receipt is left.
+
+use risc0_zkvm::{
+    get_prover_server, ExecutorEnv, ExecutorImpl, InnerReceipt, ProverOpts, ProverServer, Receipt,
+    VerifierContext,
+};
+use std::sync::Mutex;
+
+/// The receipt proved from the segments.
+pub struct SegmentsProof {
+    /// The succinct receipt of the whole execution.
+    pub receipt: Receipt,
+    pub segments: usize,
+    pub total_cycles: u64,
+}
+
+/// Executes the `elf` in the `env` and proves its segments on the `workers` threads.
+pub fn prove(env: ExecutorEnv<'_>, elf: &[u8], workers: usize) -> anyhow::Result<SegmentsProof> {
+    let mut session = ExecutorImpl::from_elf(env, elf)?.run()?;
+    let journal = session
+        .journal
+        .as_ref()
+        .map(|journal| journal.bytes.clone())
+        .unwrap_or_default();
+    let total_cycles = session.total_cycles;
+    let segments = session.segments.len();
+    println!("Executed {total_cycles} cycles in {segments} segments");
+
+    let opts = ProverOpts::succinct();
+    let segment_refs = std::mem::take(&mut session.segments);
+    let mut receipts = parallel_map(segment_refs, workers, &opts, |prover, segment| {
+        let segment = segment.resolve()?;
+        let receipt = prover.prove_segment(&VerifierContext::default(), &segment)?;
+        prover.lift(&receipt)
+    })?;
+
+    while receipts.len() > 1 {
+        let mut pairs = vec![];
+        let mut odd = None;
+        let mut receipts_iter = receipts.into_iter();
+        while let Some(first) = receipts_iter.next() {
+            match receipts_iter.next() {
+                Some(second) => pairs.push((first, second)),
+                None => odd = Some(first),
+            }
+        }
+
+        receipts = parallel_map(pairs, workers, &opts, |prover, (first, second)| {
+            prover.join(&first, &second)
+        })?;
+        // The last receipt without a pair is joined on the next level.
+        receipts.extend(odd);
+    }
+
+    let succinct = receipts
+        .pop()
+        .ok_or_else(|| anyhow::anyhow!("The execution has no segments"))?;
+    Ok(SegmentsProof {
+        receipt: Receipt::new(InnerReceipt::Succinct(succinct), journal),
+        segments,
+        total_cycles,
+    })
+}
+
+/// Maps the `items` with `f` on the `workers` threads, keeping their order.
+/// Each thread creates its own prover, as the prover can't be shared between threads.
+fn parallel_map<T, R>(
+    items: Vec<T>,
+    workers: usize,
+    opts: &ProverOpts,
+    f: impl Fn(&dyn ProverServer, T) -> anyhow::Result<R> + Sync,
+) -> anyhow::Result<Vec<R>>
+where
+    T: Send,
+    R: Send,
+{
+    let len = items.len();
+    let queue = Mutex::new(items.into_iter().enumerate());
+    let results = Mutex::new((0..len).map(|_| None).collect::<Vec<Option<R>>>());
+
+    std::thread::scope(|scope| {
+        let threads = (0..workers.clamp(1, len.max(1)))
+            .map(|_| {
+                scope.spawn(|| -> anyhow::Result<()> {
+                    let prover = get_prover_server(opts)?;
+                    loop {
+                        let Some((index, item)) =
+                            queue.lock().expect("The queue is poisoned").next()
+                        else {
+                            return Ok(());
+                        };
+                        let result = f(prover.as_ref(), item)?;
+                        results.lock().expect("The results are poisoned")[index] = Some(result);
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        threads.into_iter().try_for_each(|thread| {
+            thread
+                .join()
+                .map_err(|_| anyhow::anyhow!("The proving thread panicked"))?
+        })
+    })?;
+
+    Ok(results
+        .into_inner()
+        .expect("The results are poisoned")
+        .into_iter()
+        .map(|result| result.expect("Every item is proved"))
+        .collect())
+}
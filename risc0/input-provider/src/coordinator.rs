@@ -0,0 +1,386 @@
+//! The coordinator that hands the proving jobs of the daemon to the remote workers.
+//!
+//! The [`Coordinator`] is the [`ProvingBackend`] of the daemon. It listens for the
+//! [`crate::worker::ProvingWorker`]s over TCP and sends the bincode encoded [`prover::Input`]
+//! of every job to the next idle worker, so the blocks are proved by many machines at once,
+//! see [`crate::daemon::DaemonConfig::parallel_jobs`].
+//!
+//! Workers register with the name and the verification key of their backend, and the
+//! coordinator rejects the workers that prove with another program. Workers send heartbeats
+//! while connected, and the worker that misses them for the heartbeat timeout is lost:
+//! its job fails and is retried by the daemon on another worker.
+//!
+//! The messages are bincode encoded and prefixed with their length as `u32`.
+
+use crate::daemon::{
+    BlockProof,
+    ProvingBackend,
+};
+use serde::{
+    de::DeserializeOwned,
+    Deserialize,
+    Serialize,
+};
+use std::{
+    collections::BTreeMap,
+    net::SocketAddr,
+    sync::{
+        Arc,
+        Mutex,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
+};
+use tokio::{
+    io::{
+        AsyncReadExt,
+        AsyncWriteExt,
+    },
+    net::{
+        tcp::{
+            OwnedReadHalf,
+            OwnedWriteHalf,
+        },
+        TcpListener,
+        TcpStream,
+    },
+    sync::{
+        mpsc,
+        oneshot,
+    },
+};
+
+/// The interval between the heartbeats of the worker.
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The maximum size of the message, large enough for the inputs of the biggest blocks.
+const MAX_MESSAGE_SIZE: u32 = 1 << 30;
+
+/// The message sent by the worker to the coordinator.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) enum WorkerMessage {
+    /// The first message of the worker with the name and the key of its backend.
+    Register {
+        backend: String,
+        vkey: Option<String>,
+    },
+    Heartbeat,
+    /// The result of the last job.
+    Proved(Result<BlockProof, String>),
+}
+
+/// The message sent by the coordinator to the worker.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) enum CoordinatorMessage {
+    Accepted,
+    Rejected(String),
+    /// Proves the block with the bincode encoded [`prover::Input`].
+    Prove(Vec<u8>),
+}
+
+/// Writes the `message` prefixed with its length.
+pub(crate) async fn write_message<T: Serialize>(
+    writer: &mut OwnedWriteHalf,
+    message: &T,
+) -> anyhow::Result<()> {
+    let bytes = bincode::serialize(message)?;
+    let len = u32::try_from(bytes.len())
+        .ok()
+        .filter(|len| *len <= MAX_MESSAGE_SIZE)
+        .ok_or_else(|| {
+            anyhow::anyhow!("The message of {} bytes is too big", bytes.len())
+        })?;
+    writer.write_u32(len).await?;
+    writer.write_all(&bytes).await?;
+    Ok(())
+}
+
+async fn read_message<T: DeserializeOwned>(
+    reader: &mut OwnedReadHalf,
+) -> anyhow::Result<T> {
+    let len = reader
+        .read_u32()
+        .await
+        .map_err(|e| anyhow::anyhow!("The connection is closed: {e}"))?;
+    anyhow::ensure!(
+        len <= MAX_MESSAGE_SIZE,
+        "The message of {len} bytes is too big"
+    );
+    let mut bytes = vec![0; len as usize];
+    reader.read_exact(&mut bytes).await?;
+    Ok(bincode::deserialize(&bytes)?)
+}
+
+/// Reads the messages from the `reader` in the background, so a message is never
+/// partially read when the receiving future is cancelled. The channel is closed after
+/// the first error.
+pub(crate) fn read_messages<T>(
+    mut reader: OwnedReadHalf,
+) -> mpsc::Receiver<anyhow::Result<T>>
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    let (sender, receiver) = mpsc::channel(16);
+    tokio::spawn(async move {
+        loop {
+            let message = read_message(&mut reader).await;
+            let failed = message.is_err();
+            if sender.send(message).await.is_err() || failed {
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+/// The job waiting for an idle worker.
+struct Assignment {
+    input: Vec<u8>,
+    reply: oneshot::Sender<anyhow::Result<BlockProof>>,
+}
+
+/// The health of the connected worker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkerStatus {
+    pub id: u64,
+    pub address: SocketAddr,
+    /// Whether the worker is proving a block.
+    pub proving: bool,
+    pub proved: u64,
+    pub failed: u64,
+    /// When the last message was received from the worker.
+    pub last_seen: Instant,
+}
+
+/// Proves the blocks with the remote workers.
+#[derive(Clone)]
+pub struct Coordinator {
+    backend: &'static str,
+    vkey: Option<String>,
+    heartbeat_timeout: Duration,
+    assignments: mpsc::Sender<Assignment>,
+    queue: Arc<tokio::sync::Mutex<mpsc::Receiver<Assignment>>>,
+    workers: Arc<Mutex<BTreeMap<u64, WorkerStatus>>>,
+}
+
+impl Coordinator {
+    /// Creates the coordinator that accepts the workers proving with the same program
+    /// as the `backend`.
+    pub fn new(backend: &impl ProvingBackend) -> Self {
+        let (assignments, queue) = mpsc::channel(1024);
+        Self {
+            backend: backend.name(),
+            vkey: backend.vkey(),
+            heartbeat_timeout: HEARTBEAT_INTERVAL * 3,
+            assignments,
+            queue: Arc::new(tokio::sync::Mutex::new(queue)),
+            workers: Default::default(),
+        }
+    }
+
+    /// Sets the time without messages after which the worker is lost.
+    pub fn with_heartbeat_timeout(mut self, heartbeat_timeout: Duration) -> Self {
+        self.heartbeat_timeout = heartbeat_timeout;
+        self
+    }
+
+    /// Returns the connected workers.
+    pub fn workers(&self) -> Vec<WorkerStatus> {
+        self.workers
+            .lock()
+            .expect("The workers are poisoned")
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    /// Accepts the workers on the `listener` until the task is dropped.
+    pub async fn serve(self, listener: TcpListener) -> anyhow::Result<()> {
+        tracing::info!(
+            "The coordinator is listening for workers on {}",
+            listener.local_addr()?
+        );
+        let mut next_id = 0;
+        loop {
+            let (stream, address) = listener.accept().await?;
+            next_id += 1;
+            let id = next_id;
+            let coordinator = self.clone();
+            tokio::spawn(async move {
+                match coordinator.handle_worker(id, address, stream).await {
+                    Ok(()) => tracing::info!("The worker {id} at {address} is stopped"),
+                    Err(e) => tracing::warn!("The worker {id} at {address} is lost: {e}"),
+                }
+                coordinator
+                    .workers
+                    .lock()
+                    .expect("The workers are poisoned")
+                    .remove(&id);
+            });
+        }
+    }
+
+    /// Registers the worker and hands it the jobs until it's lost.
+    async fn handle_worker(
+        &self,
+        id: u64,
+        address: SocketAddr,
+        stream: TcpStream,
+    ) -> anyhow::Result<()> {
+        let (reader, mut writer) = stream.into_split();
+        let mut messages = read_messages::<WorkerMessage>(reader);
+
+        let (backend, vkey) = match self.receive(&mut messages).await? {
+            WorkerMessage::Register { backend, vkey } => (backend, vkey),
+            message => anyhow::bail!("Expected the registration, got {message:?}"),
+        };
+        if backend != self.backend || vkey != self.vkey {
+            let reason = format!(
+                "The worker proves with {backend} {vkey:?}, expected {} {:?}",
+                self.backend, self.vkey
+            );
+            write_message(&mut writer, &CoordinatorMessage::Rejected(reason.clone()))
+                .await?;
+            anyhow::bail!(reason);
+        }
+        write_message(&mut writer, &CoordinatorMessage::Accepted).await?;
+        self.workers
+            .lock()
+            .expect("The workers are poisoned")
+            .insert(
+                id,
+                WorkerStatus {
+                    id,
+                    address,
+                    proving: false,
+                    proved: 0,
+                    failed: 0,
+                    last_seen: Instant::now(),
+                },
+            );
+        tracing::info!("The worker {id} at {address} is registered");
+
+        loop {
+            // The idle worker still sends heartbeats.
+            let assignment = tokio::select! {
+                assignment = async { self.queue.lock().await.recv().await } => {
+                    match assignment {
+                        Some(assignment) => assignment,
+                        None => return Ok(()),
+                    }
+                }
+                message = self.receive(&mut messages) => {
+                    match message? {
+                        WorkerMessage::Heartbeat => {
+                            self.seen(id);
+                            continue;
+                        }
+                        message => anyhow::bail!("Unexpected message {message:?}"),
+                    }
+                }
+            };
+
+            self.update_worker(id, |worker| worker.proving = true);
+            let Assignment { input, reply } = assignment;
+            let result = self.prove_with(id, &mut writer, &mut messages, input).await;
+            let lost = match &result {
+                Ok(Ok(_)) => {
+                    self.update_worker(id, |worker| worker.proved += 1);
+                    None
+                }
+                Ok(Err(_)) => {
+                    self.update_worker(id, |worker| worker.failed += 1);
+                    None
+                }
+                Err(e) => Some(e.to_string()),
+            };
+            self.update_worker(id, |worker| worker.proving = false);
+
+            let proof = match result {
+                Ok(proof) => proof,
+                Err(e) => Err(anyhow::anyhow!("The worker {id} is lost: {e}")),
+            };
+            let _ = reply.send(proof);
+            if let Some(error) = lost {
+                anyhow::bail!(error);
+            }
+        }
+    }
+
+    /// Sends the `input` to the worker and waits for its proof.
+    /// Returns an error if the worker is lost, otherwise the result of proving.
+    async fn prove_with(
+        &self,
+        id: u64,
+        writer: &mut OwnedWriteHalf,
+        messages: &mut mpsc::Receiver<anyhow::Result<WorkerMessage>>,
+        input: Vec<u8>,
+    ) -> anyhow::Result<anyhow::Result<BlockProof>> {
+        write_message(writer, &CoordinatorMessage::Prove(input)).await?;
+        loop {
+            match self.receive(messages).await? {
+                WorkerMessage::Heartbeat => self.seen(id),
+                WorkerMessage::Proved(result) => {
+                    self.seen(id);
+                    return Ok(result
+                        .map_err(|e| anyhow::anyhow!("The worker {id} failed: {e}")));
+                }
+                message => anyhow::bail!("Unexpected message {message:?}"),
+            }
+        }
+    }
+
+    /// Receives the next message of the worker within the heartbeat timeout.
+    async fn receive(
+        &self,
+        messages: &mut mpsc::Receiver<anyhow::Result<WorkerMessage>>,
+    ) -> anyhow::Result<WorkerMessage> {
+        match tokio::time::timeout(self.heartbeat_timeout, messages.recv()).await {
+            Ok(Some(message)) => message,
+            Ok(None) => anyhow::bail!("The connection is closed"),
+            Err(_) => anyhow::bail!(
+                "No heartbeats for {} seconds",
+                self.heartbeat_timeout.as_secs_f32()
+            ),
+        }
+    }
+
+    fn update_worker(&self, id: u64, update: impl FnOnce(&mut WorkerStatus)) {
+        let mut workers = self.workers.lock().expect("The workers are poisoned");
+        if let Some(worker) = workers.get_mut(&id) {
+            update(worker);
+        }
+    }
+
+    /// Records that the worker has just sent a message.
+    fn seen(&self, id: u64) {
+        self.update_worker(id, |worker| worker.last_seen = Instant::now());
+    }
+}
+
+impl ProvingBackend for Coordinator {
+    fn name(&self) -> &'static str {
+        self.backend
+    }
+
+    fn vkey(&self) -> Option<String> {
+        self.vkey.clone()
+    }
+
+    /// Waits for an idle worker to prove the block.
+    /// Must be called outside of the async runtime, as the daemon does.
+    fn prove(&self, input: &[u8]) -> anyhow::Result<BlockProof> {
+        let (reply, proof) = oneshot::channel();
+        self.assignments
+            .blocking_send(Assignment {
+                input: input.to_vec(),
+                reply,
+            })
+            .map_err(|_| anyhow::anyhow!("The coordinator is stopped"))?;
+        proof
+            .blocking_recv()
+            .map_err(|_| anyhow::anyhow!("The coordinator is stopped"))?
+    }
+}
@@ -0,0 +1,596 @@
+//! The prover daemon that follows the chain of the local node and proves new blocks.
+//!
+//! The daemon subscribes to the block imports of the node, produces the [`prover::Input`]
+//! for every new block and queues the proving job in the [`JobStore`]. The jobs are
+//! proved by the [`ProvingBackend`], up to [`DaemonConfig::parallel_jobs`] at once.
+//! A failed job is queued again to be proved after [`DaemonConfig::retry_delay`], up to
+//! [`DaemonConfig::max_attempts`] times, and proved blocks are persisted in
+//! [`DaemonConfig::output_dir`], see [`ProvedBlock`]. The [`DaemonHandle`] requests the
+//! proofs of earlier blocks from the running daemon and reads the jobs, see [`crate::api`].
+
+use crate::{
+    job_store::{
+        Job,
+        JobStatus,
+        JobStore,
+    },
+    produce_prover_input_from_database,
+};
+use alloy_sol_types::SolType;
+use fuel_core::{
+    combined_database::CombinedDatabase,
+    service::FuelService,
+};
+use fuel_core_types::fuel_types::BlockHeight;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use std::{
+    fmt,
+    fs,
+    future::Future,
+    path::{
+        Path,
+        PathBuf,
+    },
+    sync::{
+        atomic::{
+            AtomicBool,
+            Ordering,
+        },
+        Arc,
+    },
+    time::{
+        Duration,
+        SystemTime,
+    },
+};
+use tokio::{
+    sync::{
+        broadcast::error::RecvError,
+        mpsc,
+        oneshot,
+        Notify,
+    },
+    task::JoinSet,
+};
+
+const INPUT_FILE: &str = "input.bin";
+const PUBLIC_VALUES_FILE: &str = "public_values.bin";
+const PROOF_FILE: &str = "proof.bin";
+const BACKEND_FILE: &str = "backend";
+const JOB_STORE_FILE: &str = "jobs.sqlite";
+
+/// The zkVM proving the blocks.
+pub trait ProvingBackend: Send + Sync + 'static {
+    /// The name of the backend, persisted with the proofs.
+    fn name(&self) -> &'static str;
+
+    /// The key of the program that verifies the proofs,
+    /// `None` if the proofs can't be verified.
+    fn vkey(&self) -> Option<String>;
+
+    /// Proves the validation of the block with the bincode encoded [`prover::Input`].
+    /// The call blocks until the proof is generated.
+    fn prove(&self, input: &[u8]) -> anyhow::Result<BlockProof>;
+}
+
+/// The proof generated by the [`ProvingBackend`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockProof {
+    /// The ABI encoded `PublicValuesStruct`.
+    pub public_values: Vec<u8>,
+    /// The proof in the format of the backend.
+    pub proof: Vec<u8>,
+    /// The cycles of the proof, if the backend reports them.
+    pub cycles: Option<u64>,
+}
+
+/// Executes the prover natively without a zkVM, so the proof is empty.
+/// It checks the whole pipeline except the zkVM.
+pub struct NativeBackend;
+
+impl ProvingBackend for NativeBackend {
+    fn name(&self) -> &'static str {
+        "native"
+    }
+
+    fn vkey(&self) -> Option<String> {
+        None
+    }
+
+    fn prove(&self, input: &[u8]) -> anyhow::Result<BlockProof> {
+        let public_values = prover::prove(input)
+            .map_err(|e| anyhow::anyhow!("Unable to prove the block: {e}"))?;
+        Ok(BlockProof {
+            public_values: prover::PublicValuesStruct::abi_encode(&public_values),
+            proof: vec![],
+            cycles: None,
+        })
+    }
+}
+
+/// The configuration of the [`ProverDaemon`].
+#[derive(Debug, Clone)]
+pub struct DaemonConfig {
+    /// The directory to persist the proved blocks.
+    pub output_dir: PathBuf,
+    /// The number of attempts to prove the block before giving up.
+    pub max_attempts: u32,
+    /// The time to wait before retrying the failed proving job.
+    pub retry_delay: Duration,
+    /// The number of jobs proved at once.
+    pub parallel_jobs: usize,
+}
+
+impl DaemonConfig {
+    pub fn new(output_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            output_dir: output_dir.into(),
+            max_attempts: 3,
+            retry_delay: Duration::from_secs(1),
+            parallel_jobs: 1,
+        }
+    }
+
+    /// Sets the number of attempts to prove the block.
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Sets the time to wait before retrying the failed proving job.
+    pub fn with_retry_delay(mut self, retry_delay: Duration) -> Self {
+        self.retry_delay = retry_delay;
+        self
+    }
+
+    /// Sets the number of jobs proved at once, for example by the remote workers,
+    /// see [`crate::coordinator`].
+    pub fn with_parallel_jobs(mut self, parallel_jobs: usize) -> Self {
+        self.parallel_jobs = parallel_jobs.max(1);
+        self
+    }
+
+    /// The path of the [`JobStore`] in the `output_dir`.
+    pub fn job_store_path(&self) -> PathBuf {
+        self.output_dir.join(JOB_STORE_FILE)
+    }
+}
+
+/// The proved block persisted in `<output_dir>/<height>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProvedBlock {
+    pub height: BlockHeight,
+    pub backend: String,
+    /// The bincode encoded [`prover::Input`].
+    pub input: Vec<u8>,
+    pub proof: BlockProof,
+}
+
+impl ProvedBlock {
+    /// The directory of the block at the `height` in the `output_dir`.
+    pub fn dir(output_dir: &Path, height: BlockHeight) -> PathBuf {
+        output_dir.join(height.to_string())
+    }
+
+    /// Saves the block into the `output_dir`.
+    pub fn save(&self, output_dir: &Path) -> anyhow::Result<()> {
+        let dir = Self::dir(output_dir, self.height);
+        fs::create_dir_all(&dir)?;
+        fs::write(dir.join(INPUT_FILE), &self.input)?;
+        fs::write(dir.join(PUBLIC_VALUES_FILE), &self.proof.public_values)?;
+        fs::write(dir.join(PROOF_FILE), &self.proof.proof)?;
+        // The backend is written last, it marks the block as completely saved.
+        fs::write(dir.join(BACKEND_FILE), &self.backend)?;
+        Ok(())
+    }
+
+    /// Loads the block at the `height` from the `output_dir`,
+    /// returns `None` if the block is not proved yet.
+    pub fn load(output_dir: &Path, height: BlockHeight) -> anyhow::Result<Option<Self>> {
+        let dir = Self::dir(output_dir, height);
+        if !dir.join(BACKEND_FILE).exists() {
+            return Ok(None);
+        }
+
+        Ok(Some(Self {
+            height,
+            backend: fs::read_to_string(dir.join(BACKEND_FILE))?,
+            input: fs::read(dir.join(INPUT_FILE))?,
+            proof: BlockProof {
+                public_values: fs::read(dir.join(PUBLIC_VALUES_FILE))?,
+                proof: fs::read(dir.join(PROOF_FILE))?,
+                cycles: None,
+            },
+        }))
+    }
+}
+
+/// The error of the request to prove the block that is not produced yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnproducedBlock {
+    pub height: BlockHeight,
+    pub latest_height: BlockHeight,
+}
+
+impl fmt::Display for UnproducedBlock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "The block at {} is not produced yet, the latest block is at {}",
+            self.height, self.latest_height
+        )
+    }
+}
+
+impl std::error::Error for UnproducedBlock {}
+
+/// The request of the [`DaemonHandle`] to prove the block at the `height`.
+struct ProveRequest {
+    height: BlockHeight,
+    reply: oneshot::Sender<anyhow::Result<Job>>,
+}
+
+/// The handle to request proofs from the running [`ProverDaemon`] and to read its jobs.
+#[derive(Clone)]
+pub struct DaemonHandle {
+    backend: &'static str,
+    vkey: Option<String>,
+    output_dir: PathBuf,
+    store: Arc<JobStore>,
+    requests: mpsc::Sender<ProveRequest>,
+}
+
+impl DaemonHandle {
+    /// The name of the backend proving the blocks.
+    pub fn backend(&self) -> &'static str {
+        self.backend
+    }
+
+    /// The key of the program that verifies the proofs.
+    pub fn vkey(&self) -> Option<&str> {
+        self.vkey.as_deref()
+    }
+
+    /// The directory of the proved blocks.
+    pub fn output_dir(&self) -> &Path {
+        &self.output_dir
+    }
+
+    pub fn store(&self) -> &JobStore {
+        &self.store
+    }
+
+    /// Requests the proof of the block at the `height` and returns its job.
+    /// The failed job is queued again. Fails with [`UnproducedBlock`] if the block
+    /// is not produced yet.
+    pub async fn prove(&self, height: BlockHeight) -> anyhow::Result<Job> {
+        let (reply, response) = oneshot::channel();
+        self.requests
+            .send(ProveRequest { height, reply })
+            .await
+            .map_err(|_| anyhow::anyhow!("The daemon is stopped"))?;
+        response
+            .await
+            .map_err(|_| anyhow::anyhow!("The daemon is stopped"))?
+    }
+}
+
+/// Follows the chain of the local node and proves new blocks with the backend.
+pub struct ProverDaemon<'a, B> {
+    fuel_node: &'a FuelService,
+    backend: Arc<B>,
+    config: DaemonConfig,
+    store: Arc<JobStore>,
+    jobs: JobQueue,
+    requests: mpsc::Receiver<ProveRequest>,
+    handle: DaemonHandle,
+}
+
+impl<'a, B> ProverDaemon<'a, B>
+where
+    B: ProvingBackend,
+{
+    /// Creates the daemon and opens its [`JobStore`] in the output directory.
+    pub fn new(
+        fuel_node: &'a FuelService,
+        backend: B,
+        config: DaemonConfig,
+    ) -> anyhow::Result<Self> {
+        fs::create_dir_all(&config.output_dir)?;
+        let store = Arc::new(JobStore::open(&config.job_store_path())?);
+        let (sender, requests) = mpsc::channel(16);
+        let handle = DaemonHandle {
+            backend: backend.name(),
+            vkey: backend.vkey(),
+            output_dir: config.output_dir.clone(),
+            store: store.clone(),
+            requests: sender,
+        };
+        let jobs = JobQueue {
+            database: fuel_node.shared.database.clone(),
+            output_dir: config.output_dir.clone(),
+            store: store.clone(),
+            backend: backend.name(),
+        };
+
+        Ok(Self {
+            fuel_node,
+            backend: Arc::new(backend),
+            config,
+            store,
+            jobs,
+            requests,
+            handle,
+        })
+    }
+
+    pub fn handle(&self) -> DaemonHandle {
+        self.handle.clone()
+    }
+
+    /// Proves the blocks imported by the node until the `shutdown` completes.
+    /// The jobs queued before the shutdown are proved before returning.
+    ///
+    /// After a restart, the daemon proves the jobs left in the [`JobStore`]
+    /// and the blocks imported while it was stopped.
+    pub async fn run(mut self, shutdown: impl Future<Output = ()>) -> anyhow::Result<()> {
+        let mut imports = self
+            .fuel_node
+            .shared
+            .block_importer
+            .block_importer
+            .subscribe();
+        let mut next_height = match self.store.latest_height()? {
+            Some(height) => height.succ(),
+            None => self.jobs.latest_height()?.succ(),
+        };
+
+        let queued = Arc::new(Notify::new());
+        let following = Arc::new(AtomicBool::new(true));
+        let mut prover = tokio::spawn(prove_jobs(
+            self.backend.clone(),
+            self.config.clone(),
+            self.store.clone(),
+            queued.clone(),
+            following.clone(),
+        ));
+
+        tokio::pin!(shutdown);
+        let mut stop = false;
+        loop {
+            let latest_height = self.jobs.latest_height()?;
+            while let Some(height) = next_height.filter(|height| *height <= latest_height)
+            {
+                match self.blocking(move |jobs| jobs.queue(height)).await {
+                    Ok(()) => queued.notify_one(),
+                    Err(e) => tracing::error!(
+                        "Unable to queue the proving job for the block at {height}: {e}"
+                    ),
+                }
+                next_height = height.succ();
+            }
+            // The blocks imported before the shutdown are still queued.
+            if stop {
+                break;
+            }
+
+            let request = tokio::select! {
+                import = imports.recv() => {
+                    // The lagged imports are caught up from the database.
+                    stop = matches!(import, Err(RecvError::Closed));
+                    None
+                }
+                Some(request) = self.requests.recv() => Some(request),
+                _ = &mut shutdown => {
+                    stop = true;
+                    None
+                }
+                // The prover stops while following the chain only on the job store errors.
+                result = &mut prover => {
+                    return result.map_err(|e| anyhow::anyhow!("The prover panicked: {e}"))?;
+                }
+            };
+            if let Some(ProveRequest { height, reply }) = request {
+                let result = self.blocking(move |jobs| jobs.request(height)).await;
+                if result.is_ok() {
+                    queued.notify_one();
+                }
+                let _ = reply.send(result);
+            }
+        }
+
+        following.store(false, Ordering::SeqCst);
+        queued.notify_one();
+        prover
+            .await
+            .map_err(|e| anyhow::anyhow!("The prover panicked: {e}"))?
+    }
+
+    /// Runs `f` on the [`JobQueue`] in the blocking pool, so producing the inputs,
+    /// writing them and the job store don't block the task that follows the chain.
+    async fn blocking<T, F>(&self, f: F) -> anyhow::Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&JobQueue) -> anyhow::Result<T> + Send + 'static,
+    {
+        let jobs = self.jobs.clone();
+        tokio::task::spawn_blocking(move || f(&jobs))
+            .await
+            .map_err(|e| anyhow::anyhow!("The job queue panicked: {e}"))?
+    }
+}
+
+/// Produces the inputs of the blocks from the database of the node and queues their jobs.
+/// It owns its state, so the daemon moves it to the blocking pool.
+#[derive(Clone)]
+struct JobQueue {
+    database: CombinedDatabase,
+    output_dir: PathBuf,
+    store: Arc<JobStore>,
+    backend: &'static str,
+}
+
+impl JobQueue {
+    /// Produces the input for the block at the `height`, saves it and queues the job.
+    fn queue(&self, height: BlockHeight) -> anyhow::Result<()> {
+        let input = produce_prover_input_from_database(&self.database, height)?;
+        let dir = ProvedBlock::dir(&self.output_dir, height);
+        fs::create_dir_all(&dir)?;
+        let input_path = dir.join(INPUT_FILE);
+        fs::write(&input_path, bincode::serialize(&input)?)?;
+
+        let block_id = input.block.header().id();
+        self.store
+            .insert(height, &block_id, &input_path, self.backend)?;
+        tracing::info!("Queued the proving job for the block at {height}");
+        Ok(())
+    }
+
+    /// Queues the job requested by the [`DaemonHandle`] unless it is already queued or proved.
+    fn request(&self, height: BlockHeight) -> anyhow::Result<Job> {
+        let latest_height = self.latest_height()?;
+        if height > latest_height {
+            return Err(UnproducedBlock {
+                height,
+                latest_height,
+            }
+            .into());
+        }
+
+        match self.store.get(height)? {
+            Some(job) if job.status == JobStatus::Failed => self.store.requeue(height)?,
+            Some(_) => {}
+            None => self.queue(height)?,
+        }
+        self.store.get(height)?.ok_or_else(|| {
+            anyhow::anyhow!("The job for the block at {height} is not queued")
+        })
+    }
+
+    fn latest_height(&self) -> anyhow::Result<BlockHeight> {
+        self.database
+            .on_chain()
+            .latest_height()
+            .ok_or_else(|| anyhow::anyhow!("The node has no blocks"))
+    }
+}
+
+/// Proves the queued jobs, up to [`DaemonConfig::parallel_jobs`] at once,
+/// until the daemon stops `following` the chain and no jobs are queued.
+/// The jobs delayed by a retry are started once their retry delay passes.
+async fn prove_jobs<B>(
+    backend: Arc<B>,
+    config: DaemonConfig,
+    store: Arc<JobStore>,
+    queued: Arc<Notify>,
+    following: Arc<AtomicBool>,
+) -> anyhow::Result<()>
+where
+    B: ProvingBackend,
+{
+    let mut proving = JoinSet::new();
+    loop {
+        while proving.len() < config.parallel_jobs {
+            let Some(job) = store.next_queued()? else {
+                break;
+            };
+            store.start(job.height)?;
+            proving.spawn(prove_job(
+                backend.clone(),
+                config.clone(),
+                store.clone(),
+                job,
+            ));
+        }
+        if proving.is_empty()
+            && !following.load(Ordering::SeqCst)
+            && store.count(JobStatus::Queued)? == 0
+        {
+            return Ok(());
+        }
+
+        let retry_delay = store
+            .next_retry()?
+            .map(|retry| retry.duration_since(SystemTime::now()).unwrap_or_default());
+        tokio::select! {
+            Some(result) = proving.join_next() => {
+                if let Err(e) = result {
+                    tracing::error!("The proving job panicked: {e}");
+                }
+            }
+            _ = queued.notified() => {}
+            _ = tokio::time::sleep(retry_delay.unwrap_or_default()),
+                if retry_delay.is_some() => {}
+        }
+    }
+}
+
+/// Proves the started `job` and records the result in the `store`. The failed job is
+/// queued again with the retry delay if it has attempts left, so the retry doesn't hold
+/// a slot of the parallel jobs. The errors of the store are logged, so they don't stop
+/// the prover.
+async fn prove_job<B>(
+    backend: Arc<B>,
+    config: DaemonConfig,
+    store: Arc<JobStore>,
+    job: Job,
+) where
+    B: ProvingBackend,
+{
+    let height = job.height;
+    let attempt = job.attempts.saturating_add(1);
+    let Err(e) = prove_and_save(backend, &config, &store, job).await else {
+        tracing::info!("Proved the block at {height}");
+        return;
+    };
+
+    let retry_delay = (attempt < config.max_attempts).then_some(config.retry_delay);
+    if retry_delay.is_some() {
+        tracing::warn!("Attempt {attempt} to prove the block at {height} failed: {e}");
+    } else {
+        tracing::error!(
+            "Unable to prove the block at {height} after {attempt} attempts: {e}"
+        );
+    }
+    if let Err(e) = store.fail(height, &e.to_string(), retry_delay) {
+        tracing::error!(
+            "Unable to record the failed attempt to prove the block at {height}: {e}"
+        );
+    }
+}
+
+/// Proves the `job` with the `backend`, saves the proved block and marks the job as proved.
+async fn prove_and_save<B>(
+    backend: Arc<B>,
+    config: &DaemonConfig,
+    store: &JobStore,
+    job: Job,
+) -> anyhow::Result<()>
+where
+    B: ProvingBackend,
+{
+    let name = backend.name();
+    let input_path = job.input_path.clone();
+    let (input, proof) = tokio::task::spawn_blocking(move || -> anyhow::Result<_> {
+        let input = fs::read(&input_path)?;
+        let proof = backend.prove(&input)?;
+        Ok((input, proof))
+    })
+    .await
+    .map_err(|e| anyhow::anyhow!("The backend panicked: {e}"))??;
+
+    let cycles = proof.cycles;
+    let proved_block = ProvedBlock {
+        height: job.height,
+        backend: name.to_string(),
+        input,
+        proof,
+    };
+    proved_block.save(&config.output_dir)?;
+    let proof_path = ProvedBlock::dir(&config.output_dir, job.height);
+    store.complete(job.height, &proof_path, cycles)?;
+    Ok(())
+}
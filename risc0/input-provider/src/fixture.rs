@@ -0,0 +1,157 @@
+//! The fixtures for testing the verification of the proofs inside Solidity.
+//!
+//! A fixture is exported for the EVM-compatible proof of a block by any zkVM. It contains
+//! the proof, the key of the program that verifies it, the ABI encoded public values and
+//! their [`DecodedPublicValues`], so the Solidity tests can check the decoding too.
+
+use alloy_sol_types::SolType;
+use prover::PublicValuesStruct;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use std::{
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+/// The zkVM that generated the proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Zkvm {
+    Risc0,
+    Sp1,
+}
+
+impl Zkvm {
+    fn name(&self) -> &'static str {
+        match self {
+            Zkvm::Risc0 => "risc0",
+            Zkvm::Sp1 => "sp1",
+        }
+    }
+}
+
+/// The fields of the ABI encoded `PublicValuesStruct`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedPublicValues {
+    /// The tag of the [`prover::Mode`] that proved the block.
+    pub mode: u8,
+    pub block_id: [u8; 32],
+    pub parent_block_id: [u8; 32],
+    pub block_height: u32,
+    pub input_hash: [u8; 32],
+    pub consensus_parameters_version: u32,
+    pub consensus_parameters_hash: [u8; 32],
+    pub processed_relayed_transactions: Vec<[u8; 32]>,
+    pub failed_relayed_transactions: Vec<[u8; 32]>,
+    pub message_outbox_root: [u8; 32],
+    pub message_ids: Vec<[u8; 32]>,
+}
+
+impl DecodedPublicValues {
+    /// Decodes the ABI encoded `public_values`.
+    pub fn decode(public_values: &[u8]) -> anyhow::Result<Self> {
+        let PublicValuesStruct {
+            mode,
+            input_hash,
+            block_id,
+            parent_block_id,
+            block_height,
+            consensus_parameters_version,
+            consensus_parameters_hash,
+            processed_relayed_transactions,
+            failed_relayed_transactions,
+            message_outbox_root,
+            message_ids,
+        } = PublicValuesStruct::abi_decode(public_values, true)
+            .map_err(|e| anyhow::anyhow!("Unable to decode the public values: {e}"))?;
+
+        Ok(Self {
+            mode,
+            block_id: block_id.to_be_bytes(),
+            parent_block_id: parent_block_id.to_be_bytes(),
+            block_height,
+            input_hash: input_hash.to_be_bytes(),
+            consensus_parameters_version,
+            consensus_parameters_hash: consensus_parameters_hash.to_be_bytes(),
+            processed_relayed_transactions: processed_relayed_transactions
+                .into_iter()
+                .map(|id| id.0)
+                .collect(),
+            failed_relayed_transactions: failed_relayed_transactions
+                .into_iter()
+                .map(|id| id.0)
+                .collect(),
+            message_outbox_root: message_outbox_root.0,
+            message_ids: message_ids.into_iter().map(|id| id.0).collect(),
+        })
+    }
+}
+
+/// A fixture of the EVM-compatible proof of the block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProofFixture {
+    pub zkvm: Zkvm,
+    /// The proof system of the proof, for example `groth16` or `plonk`.
+    pub proof_system: String,
+    #[serde(flatten)]
+    pub decoded: DecodedPublicValues,
+    /// The key of the program checked by the verifier contract:
+    /// the verification key hash for SP1 and the image id for RISC Zero.
+    pub vkey: String,
+    /// The ABI encoded `PublicValuesStruct`.
+    pub public_values: String,
+    /// The proof in the format of the verifier contract:
+    /// the proof bytes for SP1 and the encoded seal for RISC Zero.
+    pub proof: String,
+}
+
+impl ProofFixture {
+    /// Creates the fixture of the `proof` with the ABI encoded `public_values`.
+    pub fn new(
+        zkvm: Zkvm,
+        proof_system: &str,
+        vkey: String,
+        public_values: &[u8],
+        proof: &[u8],
+    ) -> anyhow::Result<Self> {
+        Ok(Self {
+            zkvm,
+            proof_system: proof_system.to_lowercase(),
+            decoded: DecodedPublicValues::decode(public_values)?,
+            vkey,
+            public_values: format!("0x{}", hex::encode(public_values)),
+            proof: format!("0x{}", hex::encode(proof)),
+        })
+    }
+
+    /// The name of the fixture file, unique for the block and the proof system.
+    pub fn file_name(&self) -> String {
+        format!(
+            "{}-{}-{}.json",
+            self.zkvm.name(),
+            self.proof_system,
+            hex::encode(self.decoded.block_id)
+        )
+    }
+
+    /// Saves the fixture into the `dir`, creating it if it doesn't exist.
+    /// Returns the path of the fixture file.
+    pub fn save(&self, dir: &Path) -> anyhow::Result<PathBuf> {
+        fs::create_dir_all(dir).map_err(|e| {
+            anyhow::anyhow!("Unable to create the directory {}: {e}", dir.display())
+        })?;
+        let path = dir.join(self.file_name());
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| anyhow::anyhow!("Unable to encode the fixture: {e}"))?;
+        fs::write(&path, json)
+            .map_err(|e| anyhow::anyhow!("Unable to write {}: {e}", path.display()))?;
+        Ok(path)
+    }
+}
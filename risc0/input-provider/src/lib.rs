@@ -1,67 +1,210 @@
-use crate::{relayer_recorer::RelayerRecorder, storage_access_recorder::StorageAccessRecorder};
+use crate::{
+    relayer_recorer::RelayerRecorder,
+    relayer_simulation::RelayerSimulation,
+    storage_access_recorder::StorageAccessRecorder,
+    workload::{ManualBlockProduction, TransactionSender, Workload, DEFAULT_INCLUSION_TIMEOUT},
+};
 use fuel_core::{
-    chain_config::{ChainConfig, StateConfig, TESTNET_WALLET_SECRETS},
+    chain_config::{
+        ChainConfig, SnapshotMetadata, SnapshotReader, StateConfig, TESTNET_WALLET_SECRETS,
+    },
+    combined_database::CombinedDatabase,
     service::{Config, FuelService},
     state::historical_rocksdb::StateRewindPolicy,
 };
-use fuel_core_executor::executor::{ExecutionInstance, ExecutionOptions};
-use fuel_core_storage::transactional::{AtomicView, HistoricalView};
+use fuel_core_client::client::FuelClient;
+use fuel_core_executor::executor::{ExecutionInstance, ExecutionOptions, OnceTransactionsSource};
+use fuel_core_poa::Trigger;
+use fuel_core_storage::{
+    column::Column,
+    kv_store::KeyValueInspect,
+    structured_storage::StructuredStorage,
+    tables::FuelBlocks,
+    transactional::{AtomicView, HistoricalView},
+    StorageAsRef,
+};
 use fuel_core_types::{
-    fuel_asm::{op, RegId},
+    blockchain::{
+        block::Block,
+        header::{ApplicationHeader, ConsensusHeader, PartialBlockHeader},
+        primitives::DaBlockHeight,
+    },
     fuel_crypto::SecretKey,
-    fuel_tx::{Bytes32, ConsensusParameters},
+    fuel_tx::{
+        Address, Bytes32, ConsensusParameters, ContractId, Input, Transaction, UniqueIdentifier,
+    },
+    fuel_types::{BlockHeight, Nonce},
+    services::block_producer::Components,
+    tai64::Tai64,
 };
-use fuels::{
-    accounts::Account,
-    prelude::{Provider, WalletUnlocked},
-    types::BlockHeight,
+use fuels::prelude::{Provider, WalletUnlocked};
+use std::{
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    time::Duration,
 };
-use fuels_core::types::transaction_builders::{BuildableTransaction, ScriptTransactionBuilder};
-use std::{net::SocketAddr, path::Path};
 
+pub mod api;
+pub mod coordinator;
+pub mod daemon;
+pub mod fixture;
+pub mod job_store;
 pub mod logs;
+pub mod profile;
 pub mod relayer_recorer;
+pub mod relayer_simulation;
 pub mod storage_access_recorder;
+pub mod worker;
+pub mod workload;
 
 const CONSENSUS_PARAMETERS: &[u8] = include_bytes!("consensus_parameters.json");
 
-async fn send_script_transaction(wallet: &WalletUnlocked) -> anyhow::Result<BlockHeight> {
-    let script = [
-        op::movi(0x10, 1024),
-        op::addi(0x11, 0x10, 1024),
-        op::jmpb(RegId::ZERO, 0),
-    ]
-    .into_iter()
-    .collect();
+pub struct Service {
+    pub fuel_node: FuelService,
+    pub input: prover::Input,
+}
 
-    let mut builder = ScriptTransactionBuilder::default().with_script(script);
-    wallet.add_witnesses(&mut builder)?;
-    wallet.adjust_for_fee(&mut builder, 0).await?;
-    let provider = wallet.provider().expect("No provider");
-    let tx = builder.build(provider).await?;
+/// The node with the inputs for the consecutive blocks.
+pub struct ChainService {
+    pub fuel_node: FuelService,
+    pub inputs: Vec<prover::Input>,
+}
 
-    let tx_id = provider.send_transaction(tx).await?;
+/// The node with the input to prove one transaction and the id of the transaction.
+pub struct TransactionService {
+    pub fuel_node: FuelService,
+    pub input: prover::TransactionInput,
+    pub tx_id: Bytes32,
+}
 
-    // Sleep to await the transaction inclusion in off chain database.
-    tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+/// The configuration of the local node used to produce the prover input.
+#[derive(Debug, Clone)]
+pub struct NodeConfig {
+    /// The directory with the chain snapshot in the same format as `fuel-core` uses.
+    /// If not set, the local testnet chain config with the `consensus_parameters.json` is used.
+    pub snapshot: Option<PathBuf>,
+    /// The chain config that overrides the chain config of the chain.
+    pub chain_config: Option<ChainConfig>,
+    /// The state config that overrides the state of the chain.
+    pub state_config: Option<StateConfig>,
+    /// The secret key of the wallet that pays for transactions.
+    /// It must own coins in the state of the chain.
+    pub wallet_secret: SecretKey,
+    /// Produces blocks manually with fixed timestamps instead of the instant trigger,
+    /// so the same workload always yields the same prover input.
+    pub deterministic: bool,
+    /// The time to wait for the inclusion of each transaction into the block.
+    pub inclusion_timeout: Duration,
+}
 
-    let inclusion_block_height = provider
-        .get_transaction_by_id(&tx_id)
-        .await
-        .expect("No transaction")
-        .expect("No transaction")
-        .block_height
-        .expect("No block height");
+impl Default for NodeConfig {
+    fn default() -> Self {
+        // Get the secret for the genesis wallet
+        let secret_key: Bytes32 = TESTNET_WALLET_SECRETS[0]
+            .parse()
+            .expect("Invalid secret key");
+        let wallet_secret = SecretKey::try_from(secret_key).expect("Invalid secret key");
 
-    Ok(inclusion_block_height)
+        Self {
+            snapshot: None,
+            chain_config: None,
+            state_config: None,
+            wallet_secret,
+            deterministic: false,
+            inclusion_timeout: DEFAULT_INCLUSION_TIMEOUT,
+        }
+    }
 }
 
-pub struct Service {
-    pub fuel_node: FuelService,
-    pub input: prover::Input,
+impl NodeConfig {
+    /// Uses the chain config and the state from the snapshot directory.
+    pub fn with_snapshot(mut self, snapshot: impl Into<PathBuf>) -> Self {
+        self.snapshot = Some(snapshot.into());
+        self
+    }
+
+    /// Overrides the chain config of the chain.
+    pub fn with_chain_config(mut self, chain_config: ChainConfig) -> Self {
+        self.chain_config = Some(chain_config);
+        self
+    }
+
+    /// Overrides the state of the chain.
+    pub fn with_state_config(mut self, state_config: StateConfig) -> Self {
+        self.state_config = Some(state_config);
+        self
+    }
+
+    /// Uses the wallet with the `wallet_secret` to pay for transactions.
+    pub fn with_wallet_secret(mut self, wallet_secret: SecretKey) -> Self {
+        self.wallet_secret = wallet_secret;
+        self
+    }
+
+    /// Overrides the state of the chain with the state config JSON file,
+    /// in the format of the `state_config.json` of the snapshot.
+    pub fn with_state_config_file(self, path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path)
+            .map_err(|e| anyhow::anyhow!("Unable to read {}: {e}", path.display()))?;
+        let state_config = serde_json::from_slice(&bytes)
+            .map_err(|e| anyhow::anyhow!("Invalid state config {}: {e}", path.display()))?;
+        Ok(self.with_state_config(state_config))
+    }
+
+    /// Uses the wallet with the hex encoded `wallet_secret` to pay for transactions.
+    pub fn with_wallet_secret_hex(self, wallet_secret: &str) -> anyhow::Result<Self> {
+        let wallet_secret: Bytes32 = wallet_secret
+            .parse()
+            .map_err(|e| anyhow::anyhow!("Invalid wallet secret: {e}"))?;
+        let wallet_secret = SecretKey::try_from(wallet_secret)
+            .map_err(|e| anyhow::anyhow!("Invalid wallet secret: {e}"))?;
+        Ok(self.with_wallet_secret(wallet_secret))
+    }
+
+    /// Enables the deterministic block production.
+    pub fn with_deterministic_block_production(mut self) -> Self {
+        self.deterministic = true;
+        self
+    }
+
+    /// Sets the time to wait for the inclusion of each transaction into the block.
+    pub fn with_inclusion_timeout(mut self, inclusion_timeout: Duration) -> Self {
+        self.inclusion_timeout = inclusion_timeout;
+        self
+    }
+
+    /// Returns the chain config used by the node.
+    pub fn chain_config(&self) -> anyhow::Result<ChainConfig> {
+        if let Some(chain_config) = &self.chain_config {
+            return Ok(chain_config.clone());
+        }
+
+        match &self.snapshot {
+            Some(snapshot) => Ok(snapshot_reader(snapshot)?.chain_config().clone()),
+            None => Ok(local_testnet_chain_config()),
+        }
+    }
+
+    /// Returns the state config used by the node.
+    pub fn state_config(&self) -> anyhow::Result<StateConfig> {
+        if let Some(state_config) = &self.state_config {
+            return Ok(state_config.clone());
+        }
+
+        match &self.snapshot {
+            Some(snapshot) => StateConfig::from_reader(&snapshot_reader(snapshot)?),
+            None => Ok(StateConfig::local_testnet()),
+        }
+    }
+}
+
+fn snapshot_reader(snapshot: &Path) -> anyhow::Result<SnapshotReader> {
+    let metadata = SnapshotMetadata::read(snapshot)?;
+    SnapshotReader::open(metadata)
 }
 
-fn get_config(path: &Path) -> Config {
+fn local_testnet_chain_config() -> ChainConfig {
     let mut consensus_parameters =
         serde_json::from_slice::<ConsensusParameters>(CONSENSUS_PARAMETERS).expect("Invalid JSON");
 
@@ -71,24 +214,37 @@ fn get_config(path: &Path) -> Config {
     consensus_parameters.set_base_asset_id(new_base_asset_id);
 
     let mut chain_config = ChainConfig::local_testnet();
-    chain_config.consensus_parameters = consensus_parameters.clone();
+    chain_config.consensus_parameters = consensus_parameters;
+    chain_config
+}
+
+fn get_config(path: &Path, node_config: &NodeConfig) -> anyhow::Result<Config> {
+    let mut config = match node_config {
+        NodeConfig {
+            snapshot: Some(snapshot),
+            chain_config: None,
+            state_config: None,
+            ..
+        } => Config::local_node_with_reader(snapshot_reader(snapshot)?),
+        _ => Config::local_node_with_configs(
+            node_config.chain_config()?,
+            node_config.state_config()?,
+        ),
+    };
 
-    let mut config = Config::local_node_with_configs(chain_config, state_config);
     config.combined_db_config.state_rewind_policy = StateRewindPolicy::RewindFullRange;
     config.combined_db_config.database_path = path.to_path_buf();
     config.utxo_validation = true;
     config.txpool.utxo_validation = true;
 
-    config
-}
+    if node_config.deterministic {
+        config.block_production = Trigger::Never;
+    }
 
-async fn get_wallet(socket: SocketAddr) -> WalletUnlocked {
-    // Get the secret for the genesis wallet
-    let secret_key: Bytes32 = TESTNET_WALLET_SECRETS[0]
-        .parse()
-        .expect("Invalid secret key");
-    let secret_key = SecretKey::try_from(secret_key).expect("Invalid secret key");
+    Ok(config)
+}
 
+async fn get_wallet(socket: SocketAddr, secret_key: SecretKey) -> WalletUnlocked {
     let url = format!("http://{}", socket);
     let provider = Provider::connect(url)
         .await
@@ -98,23 +254,187 @@ async fn get_wallet(socket: SocketAddr) -> WalletUnlocked {
 }
 
 pub async fn start_node_with_transaction_and_produce_prover_input() -> anyhow::Result<Service> {
+    start_node_with_config_and_produce_prover_input(NodeConfig::default()).await
+}
+
+pub async fn start_node_with_config_and_produce_prover_input(
+    node_config: NodeConfig,
+) -> anyhow::Result<Service> {
+    start_node_with_workload_and_produce_prover_input(node_config, Workload::Script).await
+}
+
+pub async fn start_node_with_workload_and_produce_prover_input(
+    node_config: NodeConfig,
+    workload: Workload,
+) -> anyhow::Result<Service> {
     // Suggest to set "RUST_LOG=info;FUEL_TRACE=1" to see the logs
     // If you want to change the block gas limit,
     // please update next values in the `consensus_parameters.json`:
     // `max_gas_per_tx`, `max_gas_per_predicate` and `block_gas_limit`
+    let node_config = workload.prepare(node_config)?;
     let tmp = tempfile::tempdir().expect("Unable to create temp dir");
-    let fuel_node = FuelService::new_node(get_config(tmp.path())).await?;
+    let fuel_node = FuelService::new_node(get_config(tmp.path(), &node_config)?).await?;
 
-    let wallet = get_wallet(fuel_node.bound_address).await;
-    let tx_inclusion_block_height = send_script_transaction(&wallet).await?;
+    let sender = transaction_sender(&fuel_node, &node_config).await;
+    let tx_inclusion_block_height = sender.send(workload).await?;
 
-    let on_chain_database = fuel_node.shared.database.on_chain();
-    let block_height_before_tx = tx_inclusion_block_height.pred().expect("Impossible");
-    let on_chain_storage_at_height = on_chain_database.view_at(&block_height_before_tx)?;
+    let input = produce_prover_input(&fuel_node, tx_inclusion_block_height)?;
+
+    Ok(Service { fuel_node, input })
+}
+
+/// Starts the node, sends `workloads` one after another and produces inputs
+/// for all blocks created by them. The blocks are consecutive, so their proofs
+/// can be aggregated.
+pub async fn start_node_with_workloads_and_produce_prover_inputs(
+    node_config: NodeConfig,
+    workloads: &[Workload],
+) -> anyhow::Result<ChainService> {
+    let node_config = workloads
+        .iter()
+        .try_fold(node_config, |node_config, workload| {
+            workload.prepare(node_config)
+        })?;
+    let tmp = tempfile::tempdir().expect("Unable to create temp dir");
+    let fuel_node = FuelService::new_node(get_config(tmp.path(), &node_config)?).await?;
+
+    let first_block_height = fuel_node
+        .shared
+        .database
+        .on_chain()
+        .latest_height()
+        .ok_or_else(|| anyhow::anyhow!("The node has no blocks"))?
+        .succ()
+        .ok_or_else(|| anyhow::anyhow!("The block height overflow"))?;
+
+    let sender = transaction_sender(&fuel_node, &node_config).await;
+    let mut last_block_height = None;
+    for workload in workloads {
+        last_block_height = Some(sender.send(*workload).await?);
+    }
+    let last_block_height =
+        last_block_height.ok_or_else(|| anyhow::anyhow!("No workloads to send"))?;
+
+    let inputs = (*first_block_height..=*last_block_height)
+        .map(|height| produce_prover_input(&fuel_node, height.into()))
+        .collect::<anyhow::Result<_>>()?;
+
+    Ok(ChainService { fuel_node, inputs })
+}
+
+/// Starts the node and produces the input to prove the transfer from the wallet
+/// in the context of the next block. The transaction isn't sent to the node.
+pub async fn start_node_and_produce_prover_transaction_input(
+    node_config: NodeConfig,
+) -> anyhow::Result<TransactionService> {
+    let tmp = tempfile::tempdir().expect("Unable to create temp dir");
+    let fuel_node = FuelService::new_node(get_config(tmp.path(), &node_config)?).await?;
+
+    let sender = transaction_sender(&fuel_node, &node_config).await;
+    let transaction = sender
+        .transfer_transaction(Address::new([0xaa; 32]), 1_000)
+        .await?;
+    let chain_id = fuel_node
+        .shared
+        .config
+        .snapshot_reader
+        .chain_config()
+        .consensus_parameters
+        .chain_id();
+    let tx_id = transaction.id(&chain_id);
+
+    let input = produce_prover_transaction_input(&fuel_node, transaction)?;
+
+    Ok(TransactionService {
+        fuel_node,
+        input,
+        tx_id,
+    })
+}
+
+/// Starts the node with the `node_config` and the database in the `database_path`.
+/// The node continues the chain if the database already has it.
+pub async fn start_node(
+    node_config: &NodeConfig,
+    database_path: &Path,
+) -> anyhow::Result<FuelService> {
+    let fuel_node = FuelService::new_node(get_config(database_path, node_config)?).await?;
+    Ok(fuel_node)
+}
+
+/// Returns the sender of the workloads to the `fuel_node`,
+/// paying for them with the wallet from the `node_config`.
+pub async fn transaction_sender(
+    fuel_node: &FuelService,
+    node_config: &NodeConfig,
+) -> TransactionSender {
+    let wallet = get_wallet(fuel_node.bound_address, node_config.wallet_secret).await;
+    let consensus_parameters = fuel_node
+        .shared
+        .config
+        .snapshot_reader
+        .chain_config()
+        .consensus_parameters
+        .clone();
+    let mut sender = TransactionSender::new(
+        FuelClient::from(fuel_node.bound_address),
+        wallet,
+        node_config.wallet_secret,
+        consensus_parameters,
+    )
+    .with_inclusion_timeout(node_config.inclusion_timeout);
+    if node_config.deterministic {
+        sender = sender.with_manual_block_production(ManualBlockProduction::new(
+            fuel_node.shared.poa_adapter.clone(),
+            fuel_node.shared.database.clone(),
+        ));
+    }
+    sender
+}
+
+/// Produces the input for the prover to validate the block at `block_height`.
+pub fn produce_prover_input(
+    fuel_node: &FuelService,
+    block_height: BlockHeight,
+) -> anyhow::Result<prover::Input> {
+    produce_prover_input_from_database(&fuel_node.shared.database, block_height)
+}
+
+/// Produces the input for the prover to validate the block at `block_height`
+/// from the `database` of the node.
+pub fn produce_prover_input_from_database(
+    database: &CombinedDatabase,
+    block_height: BlockHeight,
+) -> anyhow::Result<prover::Input> {
+    let block = database
+        .on_chain()
+        .latest_view()?
+        .get_full_block(&block_height)?
+        .expect("Block is not available");
+
+    prover_input_for_block(database, block)
+}
+
+/// Produces the input for the prover to validate the `block` on top of the state
+/// at the previous block height. The `block` doesn't need to be imported by the node.
+pub fn produce_prover_input_for_block(
+    fuel_node: &FuelService,
+    block: Block,
+) -> anyhow::Result<prover::Input> {
+    prover_input_for_block(&fuel_node.shared.database, block)
+}
+
+fn prover_input_for_block(
+    database: &CombinedDatabase,
+    block: Block,
+) -> anyhow::Result<prover::Input> {
+    let on_chain_database = database.on_chain();
+    let block_height_before = block.header().height().pred().expect("Impossible");
+    let on_chain_storage_at_height = on_chain_database.view_at(&block_height_before)?;
 
     // We don't need to specify the height for the relayer.
     // Relayer stores events for all height from DA.
-    let latest_relayer = fuel_node.shared.database.relayer().latest_view()?;
+    let latest_relayer = database.relayer().latest_view()?;
 
     let storage = StorageAccessRecorder::new(on_chain_storage_at_height);
     let relayer = RelayerRecorder::new(latest_relayer);
@@ -128,11 +448,8 @@ pub async fn start_node_with_transaction_and_produce_prover_input() -> anyhow::R
         },
     );
 
-    let block = on_chain_database
-        .latest_view()?
-        .get_full_block(&tx_inclusion_block_height)?
-        .expect("Block with transaction is not available");
     let _ = validator.validate_without_commit(&block)?;
+    record_parent_block(&storage, *block.header().height())?;
 
     let input = prover::Input {
         block,
@@ -140,13 +457,372 @@ pub async fn start_node_with_transaction_and_produce_prover_input() -> anyhow::R
         relayer: relayer.into_prover_relayer(),
     };
 
+    Ok(input)
+}
+
+/// The prover commits the id of the parent block, so its header must be
+/// a part of the storage witness even if the executor doesn't read it.
+fn record_parent_block<S>(
+    storage: &StorageAccessRecorder<S>,
+    block_height: BlockHeight,
+) -> anyhow::Result<()>
+where
+    S: KeyValueInspect<Column = Column> + Clone,
+{
+    let parent_height = block_height
+        .pred()
+        .ok_or_else(|| anyhow::anyhow!("The genesis block has no parent"))?;
+    StructuredStorage::new(storage.clone())
+        .storage_as_ref::<FuelBlocks>()
+        .get(&parent_height)?
+        .ok_or_else(|| anyhow::anyhow!("The parent block at {parent_height} is not available"))?;
+    Ok(())
+}
+
+/// Produces the input for the prover to produce the block from the `header_to_produce`
+/// and `transactions` on top of the state at the previous block height.
+pub fn produce_prover_production_input(
+    fuel_node: &FuelService,
+    header_to_produce: PartialBlockHeader,
+    transactions: Vec<Transaction>,
+    coinbase_recipient: ContractId,
+    gas_price: u64,
+) -> anyhow::Result<prover::ProductionInput> {
+    let on_chain_database = fuel_node.shared.database.on_chain();
+    let block_height_before = header_to_produce
+        .consensus
+        .height
+        .pred()
+        .ok_or_else(|| anyhow::anyhow!("The genesis block can't be produced"))?;
+    let on_chain_storage_at_height = on_chain_database.view_at(&block_height_before)?;
+    let latest_relayer = fuel_node.shared.database.relayer().latest_view()?;
+
+    let storage = StorageAccessRecorder::new(on_chain_storage_at_height);
+    let relayer = RelayerRecorder::new(latest_relayer);
+
+    let producer = ExecutionInstance::new(
+        relayer.clone(),
+        storage.clone(),
+        ExecutionOptions {
+            extra_tx_checks: true,
+            backtrace: false,
+        },
+    );
+
+    let components = Components {
+        header_to_produce,
+        transactions_source: OnceTransactionsSource::new(transactions.clone()),
+        coinbase_recipient,
+        gas_price,
+    };
+    let _ = producer.produce_without_commit(components, false)?;
+    record_parent_block(&storage, header_to_produce.consensus.height)?;
+
+    let input = prover::ProductionInput {
+        application: header_to_produce.application,
+        consensus: header_to_produce.consensus,
+        transactions,
+        coinbase_recipient,
+        gas_price,
+        storage: storage.into_changes(),
+        relayer: relayer.into_prover_relayer(),
+    };
+
+    Ok(input)
+}
+
+/// Produces the input for the prover to produce the same `block` again from its header
+/// and transactions. The block must not contain relayed transactions, because the
+/// executor includes them from the relayer events, not from the list of transactions.
+pub fn produce_prover_production_input_for_block(
+    fuel_node: &FuelService,
+    block: &Block,
+) -> anyhow::Result<prover::ProductionInput> {
+    let application = block.header().application();
+    let consensus = block.header().consensus();
+    let header_to_produce = PartialBlockHeader {
+        application: ApplicationHeader {
+            da_height: application.da_height,
+            consensus_parameters_version: application.consensus_parameters_version,
+            state_transition_bytecode_version: application.state_transition_bytecode_version,
+            generated: Default::default(),
+        },
+        consensus: ConsensusHeader {
+            prev_root: consensus.prev_root,
+            height: consensus.height,
+            time: consensus.time,
+            generated: Default::default(),
+        },
+    };
+
+    // The last transaction is the `Mint` created by the executor during the production.
+    let (mint, transactions) = block
+        .transactions()
+        .split_last()
+        .ok_or_else(|| anyhow::anyhow!("The block has no transactions"))?;
+    let Transaction::Mint(mint) = mint else {
+        anyhow::bail!("The last transaction of the block is not `Mint`");
+    };
+
+    produce_prover_production_input(
+        fuel_node,
+        header_to_produce,
+        transactions.to_vec(),
+        mint.input_contract().contract_id,
+        *mint.gas_price(),
+    )
+}
+
+/// Produces the input for the prover to execute the `transaction` in the context
+/// of the next block on top of the latest block of the node. The block context
+/// keeps the DA height of the latest block, so no relayer events are processed.
+pub fn produce_prover_transaction_input(
+    fuel_node: &FuelService,
+    transaction: Transaction,
+) -> anyhow::Result<prover::TransactionInput> {
+    let on_chain_database = fuel_node.shared.database.on_chain();
+    let latest_height = on_chain_database
+        .latest_height()
+        .ok_or_else(|| anyhow::anyhow!("The node has no blocks"))?;
+    let da_height = on_chain_database
+        .latest_view()?
+        .storage_as_ref::<FuelBlocks>()
+        .get(&latest_height)?
+        .ok_or_else(|| anyhow::anyhow!("The block at {latest_height} is not available"))?
+        .header()
+        .application()
+        .da_height;
+    let header_to_produce = next_block_header(fuel_node, da_height)?;
+
+    let prover::ProductionInput {
+        application,
+        consensus,
+        mut transactions,
+        coinbase_recipient,
+        gas_price,
+        storage,
+        relayer,
+    } = produce_prover_production_input(
+        fuel_node,
+        header_to_produce,
+        vec![transaction],
+        ContractId::zeroed(),
+        0,
+    )?;
+
+    let input = prover::TransactionInput {
+        application,
+        consensus,
+        transaction: transactions.remove(0),
+        coinbase_recipient,
+        gas_price,
+        storage,
+        relayer,
+    };
+
+    Ok(input)
+}
+
+/// Returns the header template of the next block on top of the latest block of the node.
+/// The block uses the provided `da_height`, so it processes the events from the relayer
+/// database up to this height.
+pub fn next_block_header(
+    fuel_node: &FuelService,
+    da_height: DaBlockHeight,
+) -> anyhow::Result<PartialBlockHeader> {
+    let on_chain_view = fuel_node.shared.database.on_chain().latest_view()?;
+    let latest_height = fuel_node
+        .shared
+        .database
+        .on_chain()
+        .latest_height()
+        .ok_or_else(|| anyhow::anyhow!("The node has no blocks"))?;
+
+    let previous_header = on_chain_view
+        .storage_as_ref::<FuelBlocks>()
+        .get(&latest_height)?
+        .ok_or_else(|| anyhow::anyhow!("The block at {latest_height} is not available"))?
+        .header()
+        .clone();
+    // The `prev_root` is the root of the Merkle tree of all previous block ids,
+    // which the node maintains for every imported block.
+    let prev_root = on_chain_view
+        .storage_as_ref::<FuelBlocks>()
+        .root(&latest_height)?;
+
+    let header_to_produce = PartialBlockHeader {
+        application: ApplicationHeader {
+            da_height,
+            consensus_parameters_version: previous_header
+                .application()
+                .consensus_parameters_version,
+            state_transition_bytecode_version: previous_header
+                .application()
+                .state_transition_bytecode_version,
+            generated: Default::default(),
+        },
+        consensus: ConsensusHeader {
+            prev_root: prev_root.into(),
+            height: latest_height
+                .succ()
+                .ok_or_else(|| anyhow::anyhow!("The block height overflow"))?,
+            time: Tai64(
+                previous_header
+                    .time()
+                    .0
+                    .checked_add(1)
+                    .ok_or_else(|| anyhow::anyhow!("The block time overflow"))?,
+            ),
+            generated: Default::default(),
+        },
+    };
+
+    Ok(header_to_produce)
+}
+
+/// Produces the next block on top of the latest block of the node with `transactions`.
+/// Unlike the block producer of the node, it uses the provided `da_height`,
+/// so the block processes the events from the relayer database up to this height.
+///
+/// The block is not imported by the node.
+pub fn produce_block(
+    fuel_node: &FuelService,
+    da_height: DaBlockHeight,
+    transactions: Vec<Transaction>,
+) -> anyhow::Result<Block> {
+    let header_to_produce = next_block_header(fuel_node, da_height)?;
+
+    let on_chain_view = fuel_node.shared.database.on_chain().latest_view()?;
+    let latest_relayer = fuel_node.shared.database.relayer().latest_view()?;
+    let producer = ExecutionInstance::new(
+        RelayerRecorder::new(latest_relayer),
+        on_chain_view,
+        ExecutionOptions {
+            extra_tx_checks: true,
+            backtrace: false,
+        },
+    );
+
+    let components = Components {
+        header_to_produce,
+        transactions_source: OnceTransactionsSource::new(transactions),
+        coinbase_recipient: ContractId::zeroed(),
+        gas_price: 0,
+    };
+    let result = producer.produce_without_commit(components, false)?;
+
+    Ok(result.into_result().block)
+}
+
+/// Starts the node, seeds its relayer database with a deposit and two forced transactions,
+/// one of which fails, and produces the input for the block that processes them.
+pub async fn start_node_with_relayer_events_and_produce_prover_input(
+    node_config: NodeConfig,
+) -> anyhow::Result<Service> {
+    let tmp = tempfile::tempdir().expect("Unable to create temp dir");
+    let fuel_node = FuelService::new_node(get_config(tmp.path(), &node_config)?).await?;
+
+    let wallet = get_wallet(fuel_node.bound_address, node_config.wallet_secret).await;
+    let consensus_parameters = fuel_node
+        .shared
+        .config
+        .snapshot_reader
+        .chain_config()
+        .consensus_parameters
+        .clone();
+    let max_gas = consensus_parameters.tx_params().max_gas_per_tx();
+    let sender = TransactionSender::new(
+        FuelClient::from(fuel_node.bound_address),
+        wallet,
+        node_config.wallet_secret,
+        consensus_parameters,
+    );
+    let owner = Input::owner(&node_config.wallet_secret.public_key());
+
+    let mut relayer = RelayerSimulation::new(fuel_node.shared.database.relayer().clone());
+
+    // The deposit to the wallet at the first DA height.
+    let deposit_da_height = DaBlockHeight(1);
+    let deposit_nonce = Nonce::new([1; 32]);
+    let deposit_amount = 1_000_000;
+    let deposit = RelayerSimulation::message(
+        Address::zeroed(),
+        owner,
+        deposit_nonce,
+        deposit_amount,
+        deposit_da_height,
+    );
+    relayer.insert_events(deposit_da_height, &[deposit])?;
+
+    // The forced transactions at the second DA height. The second one doesn't
+    // provide enough gas, so the executor skips it and records it as failed.
+    let forced_da_height = DaBlockHeight(2);
+    let forced_tx = sender
+        .transfer_transaction(Address::new([0xaa; 32]), 1_000)
+        .await?;
+    let forced_tx = RelayerSimulation::relayed_transaction(
+        &forced_tx,
+        max_gas,
+        Nonce::new([2; 32]),
+        forced_da_height,
+    );
+    let skipped_tx = sender
+        .transfer_transaction(Address::new([0xbb; 32]), 1_000)
+        .await?;
+    let skipped_tx = RelayerSimulation::relayed_transaction(
+        &skipped_tx,
+        1,
+        Nonce::new([3; 32]),
+        forced_da_height,
+    );
+    relayer.insert_events(forced_da_height, &[forced_tx, skipped_tx])?;
+
+    // The block processes events from both DA heights
+    // and spends the deposit in the same block.
+    let spend_deposit =
+        sender.message_spend_transaction(Address::zeroed(), deposit_nonce, deposit_amount);
+    let block = produce_block(&fuel_node, forced_da_height, vec![spend_deposit])?;
+
+    let input = produce_prover_input_for_block(&fuel_node, block)?;
+
     Ok(Service { fuel_node, input })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::logs::init_logging;
+    use crate::{
+        api::{ErrorResponse, JobResponse, ProofResponse, ProveParams, StatusResponse},
+        coordinator::Coordinator,
+        daemon::{
+            BlockProof, DaemonConfig, NativeBackend, ProvedBlock, ProverDaemon, ProvingBackend,
+        },
+        fixture::{ProofFixture, Zkvm},
+        job_store::{JobStatus, JobStore},
+        logs::init_logging,
+        profile::{CycleReport, CYCLE_REPORT_PREFIX},
+        worker::ProvingWorker,
+    };
+    use alloy_sol_types::{
+        private::{FixedBytes, U256},
+        SolType,
+    };
+    use fuel_core::chain_config::SnapshotWriter;
+    use fuel_core_types::{
+        blockchain::primitives::BlockId,
+        fuel_tx::{policies::Policies, MessageId, UniqueIdentifier, UpgradePurpose},
+    };
+    use prover::{CycleTracker, Phase};
+    use std::{
+        cell::RefCell,
+        sync::atomic::{AtomicBool, Ordering},
+        time::SystemTime,
+    };
+
+    /// The structs declared by the Solidity library generated from the prover.
+    mod solidity {
+        alloy_sol_types::sol!("../contracts/src/PublicValues.sol");
+    }
 
     #[tokio::test]
     async fn prover_can_verify() {
@@ -160,5 +836,847 @@ mod tests {
         let proof = prover::prove(&serialized_input).unwrap();
         let block_id: [u8; 32] = service.input.block.header().id().into();
         assert_eq!(proof.block_id.to_be_bytes(), block_id);
+
+        let consensus_parameters = &service
+            .fuel_node
+            .shared
+            .config
+            .snapshot_reader
+            .chain_config()
+            .consensus_parameters;
+        let consensus_parameters_hash: [u8; 32] =
+            prover::consensus_parameters_hash(consensus_parameters).into();
+        assert_eq!(
+            proof.consensus_parameters_version,
+            service
+                .input
+                .block
+                .header()
+                .application()
+                .consensus_parameters_version
+        );
+        assert_eq!(
+            proof.consensus_parameters_hash.to_be_bytes(),
+            consensus_parameters_hash
+        );
+    }
+
+    #[test]
+    fn consensus_parameters_hash_is_the_upgrade_checksum() {
+        init_logging();
+        let consensus_parameters = local_testnet_chain_config().consensus_parameters;
+
+        // The upgrade transaction commits the checksum of the encoded consensus parameters
+        // that the chain records on-chain, the L1 verifier compares the committed hash with it.
+        let upgrade = Transaction::upgrade_consensus_parameters(
+            &consensus_parameters,
+            Policies::new(),
+            vec![],
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        let UpgradePurpose::ConsensusParameters { checksum, .. } = upgrade.upgrade_purpose() else {
+            panic!("The upgrade doesn't set the consensus parameters");
+        };
+        assert_eq!(
+            prover::consensus_parameters_hash(&consensus_parameters),
+            *checksum
+        );
+    }
+
+    #[tokio::test]
+    async fn prover_can_verify_workloads() {
+        init_logging();
+        for workload in Workload::ALL {
+            let service =
+                start_node_with_workload_and_produce_prover_input(NodeConfig::default(), workload)
+                    .await
+                    .unwrap_or_else(|e| panic!("Workload `{workload}` failed: {e}"));
+
+            let serialized_input = bincode::serialize(&service.input).unwrap();
+
+            let proof = prover::prove(&serialized_input)
+                .unwrap_or_else(|e| panic!("Workload `{workload}` is not proven: {e}"));
+            let block_id: [u8; 32] = service.input.block.header().id().into();
+            assert_eq!(proof.block_id.to_be_bytes(), block_id);
+        }
+    }
+
+    #[tokio::test]
+    async fn prover_can_verify_block_of_snapshot() {
+        init_logging();
+        let snapshot = tempfile::tempdir().unwrap();
+        let chain_config = ChainConfig::local_testnet();
+        SnapshotWriter::json(snapshot.path())
+            .write_state_config(StateConfig::local_testnet(), &chain_config)
+            .unwrap();
+
+        // The state and the wallet are passed like the `--state-config` and
+        // `--wallet-secret` arguments of the hosts.
+        let node_config = NodeConfig::default()
+            .with_snapshot(snapshot.path())
+            .with_state_config_file(snapshot.path().join("state_config.json"))
+            .unwrap()
+            .with_wallet_secret_hex(TESTNET_WALLET_SECRETS[1])
+            .unwrap();
+        let service =
+            start_node_with_workload_and_produce_prover_input(node_config, Workload::Script)
+                .await
+                .unwrap();
+
+        let serialized_input = bincode::serialize(&service.input).unwrap();
+        let proof = prover::prove(&serialized_input).unwrap();
+        let block_id: [u8; 32] = service.input.block.header().id().into();
+        assert_eq!(proof.block_id.to_be_bytes(), block_id);
+
+        let consensus_parameters_hash: [u8; 32] =
+            prover::consensus_parameters_hash(&chain_config.consensus_parameters).into();
+        assert_eq!(
+            proof.consensus_parameters_hash.to_be_bytes(),
+            consensus_parameters_hash
+        );
+    }
+
+    #[tokio::test]
+    async fn prover_can_verify_relayer_events() {
+        init_logging();
+        let service =
+            start_node_with_relayer_events_and_produce_prover_input(NodeConfig::default())
+                .await
+                .unwrap();
+
+        let serialized_input = bincode::serialize(&service.input).unwrap();
+
+        let proof = prover::prove(&serialized_input).unwrap();
+        let block_id: [u8; 32] = service.input.block.header().id().into();
+        assert_eq!(proof.block_id.to_be_bytes(), block_id);
+        assert_eq!(
+            service.input.block.header().application().da_height,
+            DaBlockHeight(2)
+        );
+        // The forced transaction is executed in addition to the deposit spending.
+        assert!(service.input.block.transactions().len() > 2);
+
+        // Both forced transactions are processed, but the second one is skipped.
+        assert_eq!(proof.processed_relayed_transactions.len(), 2);
+        assert_eq!(proof.failed_relayed_transactions.len(), 1);
+        assert_eq!(
+            proof.failed_relayed_transactions[0],
+            proof.processed_relayed_transactions[1]
+        );
+    }
+
+    #[tokio::test]
+    async fn prover_can_reproduce_block() {
+        init_logging();
+        let service = start_node_with_transaction_and_produce_prover_input()
+            .await
+            .unwrap();
+
+        let production_input =
+            produce_prover_production_input_for_block(&service.fuel_node, &service.input.block)
+                .unwrap();
+        let serialized_input = bincode::serialize(&production_input).unwrap();
+
+        let proof = prover::prove_production(&serialized_input).unwrap();
+        let block_id: [u8; 32] = service.input.block.header().id().into();
+        assert_eq!(proof.block_id.to_be_bytes(), block_id);
+
+        // The proofs of the block in both modes are told apart by the committed mode.
+        let validation = prover::prove(&bincode::serialize(&service.input).unwrap()).unwrap();
+        assert_eq!(proof.mode, prover::MODE_PRODUCTION);
+        assert_eq!(validation.mode, prover::MODE_VALIDATION);
+        assert_eq!(validation.block_id, proof.block_id);
+    }
+
+    #[tokio::test]
+    async fn prover_can_verify_transaction() {
+        init_logging();
+        let node_config = NodeConfig::default();
+        let tmp = tempfile::tempdir().unwrap();
+        let fuel_node = FuelService::new_node(get_config(tmp.path(), &node_config).unwrap())
+            .await
+            .unwrap();
+        let wallet = get_wallet(fuel_node.bound_address, node_config.wallet_secret).await;
+        let consensus_parameters = fuel_node
+            .shared
+            .config
+            .snapshot_reader
+            .chain_config()
+            .consensus_parameters
+            .clone();
+        let chain_id = consensus_parameters.chain_id();
+        let sender = TransactionSender::new(
+            FuelClient::from(fuel_node.bound_address),
+            wallet,
+            node_config.wallet_secret,
+            consensus_parameters,
+        );
+        let transaction = sender
+            .transfer_transaction(Address::new([0xaa; 32]), 1_000)
+            .await
+            .unwrap();
+        let tx_id = transaction.id(&chain_id);
+
+        let input = produce_prover_transaction_input(&fuel_node, transaction).unwrap();
+        let serialized_input = bincode::serialize(&input).unwrap();
+
+        let proof = prover::prove_transaction(&serialized_input).unwrap();
+        assert_eq!(proof.tx_id.0, *tx_id);
+        assert_eq!(proof.status, prover::TRANSACTION_STATUS_SUCCESS);
+        assert_ne!(proof.receipts_root.0, *prover::receipts_root(&[]));
+    }
+
+    #[tokio::test]
+    async fn prover_can_prove_transaction_in_transaction_mode() {
+        init_logging();
+        let service = start_node_and_produce_prover_transaction_input(NodeConfig::default())
+            .await
+            .unwrap();
+        let serialized_input = bincode::serialize(&service.input).unwrap();
+
+        // The host decodes the values committed by the guest in the transaction mode.
+        let output = prover::Mode::Transaction.prove(&serialized_input).unwrap();
+        let proof = prover::TransactionPublicValuesStruct::abi_decode(&output, true).unwrap();
+        assert_eq!(proof.mode, prover::MODE_TRANSACTION);
+        assert_eq!(proof.tx_id.0, *service.tx_id);
+        assert_eq!(proof.status, prover::TRANSACTION_STATUS_SUCCESS);
+    }
+
+    #[tokio::test]
+    async fn prover_commits_withdrawal_messages() {
+        init_logging();
+        let service = start_node_with_workload_and_produce_prover_input(
+            NodeConfig::default(),
+            Workload::Withdrawal,
+        )
+        .await
+        .unwrap();
+
+        let serialized_input = bincode::serialize(&service.input).unwrap();
+
+        let proof = prover::prove(&serialized_input).unwrap();
+        assert_eq!(proof.message_ids.len(), 1);
+
+        let message_ids = proof
+            .message_ids
+            .iter()
+            .map(|id| MessageId::new(id.0))
+            .collect::<Vec<_>>();
+        let message_proof = prover::message_proof(&message_ids, &message_ids[0]).unwrap();
+        assert_eq!(
+            *message_proof.message_outbox_root,
+            proof.message_outbox_root.0
+        );
+        assert!(message_proof.verify());
+    }
+
+    #[tokio::test]
+    async fn prover_can_aggregate_consecutive_blocks() {
+        init_logging();
+        let service = start_node_with_workloads_and_produce_prover_inputs(
+            NodeConfig::default(),
+            &[
+                Workload::Script,
+                Workload::Withdrawal,
+                Workload::ContractCall,
+            ],
+        )
+        .await
+        .unwrap();
+        assert!(service.inputs.len() >= 3);
+
+        let public_values = service
+            .inputs
+            .iter()
+            .map(|input| {
+                let serialized_input = bincode::serialize(input).unwrap();
+                let proof = prover::prove(&serialized_input).unwrap();
+                prover::PublicValuesStruct::abi_encode(&proof)
+            })
+            .collect::<Vec<_>>();
+
+        let program_id = prover::program_id([1; 8]);
+        let aggregated = prover::aggregate(program_id, &public_values).unwrap();
+        let first_block = service.inputs.first().unwrap().block.header();
+        let last_block = service.inputs.last().unwrap().block.header();
+        let last_block_id: [u8; 32] = last_block.id().into();
+        assert_eq!(aggregated.block_id.to_be_bytes(), last_block_id);
+        assert_eq!(aggregated.first_block_height, **first_block.height());
+        assert_eq!(aggregated.last_block_height, **last_block.height());
+        assert_eq!(aggregated.block_ids.len(), service.inputs.len());
+
+        // Blocks out of order don't form a chain.
+        let mut reordered = public_values.clone();
+        reordered.swap(0, 1);
+        assert!(prover::aggregate(program_id, &reordered).is_err());
+    }
+
+    #[tokio::test]
+    async fn deterministic_block_production_produces_identical_inputs() {
+        init_logging();
+        let mut inputs = vec![];
+        for _ in 0..2 {
+            let node_config = NodeConfig::default().with_deterministic_block_production();
+            let service = start_node_with_workload_and_produce_prover_input(
+                node_config,
+                Workload::ContractCall,
+            )
+            .await
+            .unwrap();
+            inputs.push(bincode::serialize(&service.input).unwrap());
+        }
+
+        assert_eq!(inputs[0], inputs[1]);
+
+        let first = prover::prove(&inputs[0]).unwrap();
+        let second = prover::prove(&inputs[1]).unwrap();
+        assert_eq!(first.input_hash, second.input_hash);
+    }
+
+    #[tokio::test]
+    async fn proof_fixture_contains_decoded_public_values() {
+        init_logging();
+        let service = start_node_with_workload_and_produce_prover_input(
+            NodeConfig::default(),
+            Workload::Withdrawal,
+        )
+        .await
+        .unwrap();
+        let input = bincode::serialize(&service.input).unwrap();
+        let public_values = prover::prove(&input).unwrap();
+        let encoded = prover::PublicValuesStruct::abi_encode(&public_values);
+
+        let fixture =
+            ProofFixture::new(Zkvm::Sp1, "Groth16", "0x01".to_string(), &encoded, &[2, 3]).unwrap();
+
+        let block_id: [u8; 32] = service.input.block.header().id().into();
+        assert_eq!(fixture.decoded.block_id, block_id);
+        assert_eq!(fixture.decoded.block_height, public_values.block_height);
+        assert_eq!(
+            fixture.decoded.message_ids.len(),
+            public_values.message_ids.len()
+        );
+        assert_eq!(
+            fixture.decoded.message_outbox_root,
+            public_values.message_outbox_root.0
+        );
+        assert_eq!(fixture.proof, "0x0203");
+        assert_eq!(
+            fixture.file_name(),
+            format!("sp1-groth16-{}.json", hex::encode(block_id))
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = fixture.save(dir.path()).unwrap();
+        let saved: ProofFixture = serde_json::from_slice(&std::fs::read(path).unwrap()).unwrap();
+        assert_eq!(saved, fixture);
+    }
+
+    #[tokio::test]
+    async fn public_values_round_trip_through_solidity_library() {
+        init_logging();
+        assert_eq!(
+            prover::solidity_library(),
+            include_str!("../../contracts/src/PublicValues.sol"),
+            "The Solidity library is outdated, run `cargo run -p prover --bin gen-solidity`"
+        );
+
+        let service = start_node_with_workload_and_produce_prover_input(
+            NodeConfig::default(),
+            Workload::Withdrawal,
+        )
+        .await
+        .unwrap();
+        let input = bincode::serialize(&service.input).unwrap();
+        let public_values = prover::prove(&input).unwrap();
+        let encoded = prover::PublicValuesStruct::abi_encode(&public_values);
+
+        // The Solidity structs decode the output of the guest and encode it back identically.
+        let decoded = solidity::PublicValuesStruct::abi_decode(&encoded, true).unwrap();
+        assert_eq!(decoded.mode, prover::MODE_VALIDATION);
+        assert_eq!(decoded.block_id, public_values.block_id);
+        assert_eq!(decoded.block_height, public_values.block_height);
+        assert_eq!(
+            decoded.message_outbox_root,
+            public_values.message_outbox_root
+        );
+        assert_eq!(decoded.message_ids, public_values.message_ids);
+        assert_eq!(solidity::PublicValuesStruct::abi_encode(&decoded), encoded);
+
+        let aggregated = prover::aggregate([1; 32], &[encoded]).unwrap();
+        let encoded = prover::AggregatedPublicValuesStruct::abi_encode(&aggregated);
+        let decoded = solidity::AggregatedPublicValuesStruct::abi_decode(&encoded, true).unwrap();
+        assert_eq!(decoded.block_ids, aggregated.block_ids);
+        assert_eq!(
+            solidity::AggregatedPublicValuesStruct::abi_encode(&decoded),
+            encoded
+        );
+
+        let transaction_public_values = prover::TransactionPublicValuesStruct {
+            mode: prover::MODE_TRANSACTION,
+            input_hash: U256::from(1),
+            tx_id: FixedBytes::from([2; 32]),
+            receipts_root: FixedBytes::from([3; 32]),
+            status: prover::TRANSACTION_STATUS_FAILURE,
+            consensus_parameters_version: public_values.consensus_parameters_version,
+            consensus_parameters_hash: public_values.consensus_parameters_hash,
+        };
+        let encoded = prover::TransactionPublicValuesStruct::abi_encode(&transaction_public_values);
+        let decoded = solidity::TransactionPublicValuesStruct::abi_decode(&encoded, true).unwrap();
+        assert_eq!(decoded.tx_id, transaction_public_values.tx_id);
+        assert_eq!(decoded.status, transaction_public_values.status);
+        assert_eq!(
+            solidity::TransactionPublicValuesStruct::abi_encode(&decoded),
+            encoded
+        );
+    }
+
+    /// Runs the daemon with the `backend` while the `workloads` are sent one after another.
+    /// Returns the heights of the blocks with the workloads.
+    async fn run_prover_daemon<B: ProvingBackend>(
+        backend: B,
+        config: DaemonConfig,
+        workloads: &[Workload],
+    ) -> Vec<BlockHeight> {
+        let node_config = NodeConfig::default();
+        let database = tempfile::tempdir().unwrap();
+        let fuel_node = start_node(&node_config, database.path()).await.unwrap();
+        let sender = transaction_sender(&fuel_node, &node_config).await;
+
+        let daemon = ProverDaemon::new(&fuel_node, backend, config).unwrap();
+        let (shutdown, shutdown_signal) = tokio::sync::oneshot::channel();
+        let send_workloads = async {
+            let mut heights = vec![];
+            for workload in workloads {
+                heights.push(sender.send(*workload).await.unwrap());
+            }
+            shutdown.send(()).unwrap();
+            heights
+        };
+        let (result, heights) = tokio::join!(
+            daemon.run(async {
+                let _ = shutdown_signal.await;
+            }),
+            send_workloads
+        );
+        result.unwrap();
+        heights
+    }
+
+    #[tokio::test]
+    async fn prover_daemon_proves_new_blocks() {
+        init_logging();
+        let output = tempfile::tempdir().unwrap();
+        let config = DaemonConfig::new(output.path());
+
+        let heights =
+            run_prover_daemon(NativeBackend, config, &[Workload::Script, Workload::Script]).await;
+
+        for &height in &heights {
+            let proved_block = ProvedBlock::load(output.path(), height)
+                .unwrap()
+                .expect("The block is proved");
+            assert_eq!(proved_block.backend, "native");
+            let public_values =
+                prover::PublicValuesStruct::abi_decode(&proved_block.proof.public_values, true)
+                    .unwrap();
+            assert_eq!(public_values.block_height, *height);
+            let input: prover::Input = bincode::deserialize(&proved_block.input).unwrap();
+            assert_eq!(*input.block.header().height(), height);
+        }
+
+        let store = JobStore::open(&DaemonConfig::new(output.path()).job_store_path()).unwrap();
+        for height in heights {
+            let job = store.get(height).unwrap().expect("The job is stored");
+            assert_eq!(job.status, JobStatus::Proved);
+            assert_eq!(job.backend, "native");
+            assert_eq!(job.attempts, 1);
+            assert_eq!(
+                job.proof_path,
+                Some(ProvedBlock::dir(output.path(), height))
+            );
+            assert_eq!(job.error, None);
+        }
+    }
+
+    /// Fails the first attempt to prove a block.
+    struct FlakyBackend {
+        failed: AtomicBool,
+    }
+
+    impl ProvingBackend for FlakyBackend {
+        fn name(&self) -> &'static str {
+            "flaky"
+        }
+
+        fn vkey(&self) -> Option<String> {
+            None
+        }
+
+        fn prove(&self, input: &[u8]) -> anyhow::Result<BlockProof> {
+            if !self.failed.swap(true, Ordering::SeqCst) {
+                anyhow::bail!("The first attempt fails");
+            }
+            NativeBackend.prove(input)
+        }
+    }
+
+    #[tokio::test]
+    async fn prover_daemon_retries_failed_jobs() {
+        init_logging();
+        let output = tempfile::tempdir().unwrap();
+        let backend = FlakyBackend {
+            failed: AtomicBool::new(false),
+        };
+
+        let config = DaemonConfig::new(output.path())
+            .with_max_attempts(2)
+            .with_retry_delay(Duration::ZERO);
+        let heights = run_prover_daemon(backend, config.clone(), &[Workload::Script]).await;
+        assert!(ProvedBlock::load(output.path(), heights[0])
+            .unwrap()
+            .is_some());
+        let store = JobStore::open(&config.job_store_path()).unwrap();
+        let job = store.get(heights[0]).unwrap().unwrap();
+        assert_eq!(job.status, JobStatus::Proved);
+        assert_eq!(job.attempts, 2);
+
+        // Without retries the block is not proved.
+        let output = tempfile::tempdir().unwrap();
+        let backend = FlakyBackend {
+            failed: AtomicBool::new(false),
+        };
+        let config = DaemonConfig::new(output.path()).with_max_attempts(1);
+        let heights = run_prover_daemon(backend, config.clone(), &[Workload::Script]).await;
+        assert!(ProvedBlock::load(output.path(), heights[0])
+            .unwrap()
+            .is_none());
+        let store = JobStore::open(&config.job_store_path()).unwrap();
+        let job = store.get(heights[0]).unwrap().unwrap();
+        assert_eq!(job.status, JobStatus::Failed);
+        assert_eq!(job.attempts, 1);
+        assert_eq!(job.error.as_deref(), Some("The first attempt fails"));
+    }
+
+    /// Starts the coordinator of the workers proving with the `backend`.
+    /// Returns the coordinator and its address.
+    async fn start_coordinator(backend: &impl ProvingBackend) -> (Coordinator, SocketAddr) {
+        let coordinator = Coordinator::new(backend);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(coordinator.clone().serve(listener));
+        (coordinator, address)
+    }
+
+    #[tokio::test]
+    async fn prover_daemon_proves_blocks_with_workers() {
+        init_logging();
+        let (coordinator, address) = start_coordinator(&NativeBackend).await;
+        for _ in 0..2 {
+            tokio::spawn(async move { ProvingWorker::new(NativeBackend).run(address).await });
+        }
+
+        let output = tempfile::tempdir().unwrap();
+        let config = DaemonConfig::new(output.path()).with_parallel_jobs(2);
+        let heights = run_prover_daemon(
+            coordinator.clone(),
+            config.clone(),
+            &[Workload::Script, Workload::Script, Workload::Script],
+        )
+        .await;
+
+        let store = JobStore::open(&config.job_store_path()).unwrap();
+        for &height in &heights {
+            let job = store.get(height).unwrap().unwrap();
+            assert_eq!(job.status, JobStatus::Proved);
+            assert_eq!(job.backend, "native");
+            assert!(ProvedBlock::load(output.path(), height).unwrap().is_some());
+        }
+        let workers = coordinator.workers();
+        assert_eq!(workers.len(), 2);
+        let proved: u64 = workers.iter().map(|worker| worker.proved).sum();
+        assert_eq!(proved, heights.len() as u64);
+        assert!(workers.iter().all(|worker| !worker.proving));
+    }
+
+    #[tokio::test]
+    async fn coordinator_rejects_workers_of_other_backends() {
+        init_logging();
+        let (coordinator, address) = start_coordinator(&NativeBackend).await;
+
+        let backend = FlakyBackend {
+            failed: AtomicBool::new(false),
+        };
+        let error = ProvingWorker::new(backend)
+            .run(address)
+            .await
+            .unwrap_err()
+            .to_string();
+
+        assert!(error.contains("rejected"), "{error}");
+        assert!(coordinator.workers().is_empty());
+    }
+
+    #[test]
+    fn job_store_resumes_interrupted_jobs() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("jobs.sqlite");
+        let height = BlockHeight::new(7);
+        let block_id = BlockId::from([7; 32]);
+        let input_path = dir.path().join("input.bin");
+
+        let store = JobStore::open(&path).unwrap();
+        store
+            .insert(height, &block_id, &input_path, "native")
+            .unwrap();
+        // The job is not queued twice.
+        store
+            .insert(height, &block_id, &input_path, "native")
+            .unwrap();
+        store.start(height).unwrap();
+        assert_eq!(store.next_queued().unwrap(), None);
+        drop(store);
+
+        // The job interrupted by the restart is queued again.
+        let store = JobStore::open(&path).unwrap();
+        let job = store.next_queued().unwrap().expect("The job is queued");
+        assert_eq!(job.height, height);
+        assert_eq!(job.block_id, block_id);
+        assert_eq!(job.input_path, input_path);
+        assert_eq!(job.status, JobStatus::Queued);
+        assert_eq!(job.attempts, 1);
+        assert_eq!(store.latest_height().unwrap(), Some(height));
+
+        store.start(height).unwrap();
+        store.complete(height, dir.path(), Some(1000)).unwrap();
+        let job = store.get(height).unwrap().unwrap();
+        assert_eq!(job.status, JobStatus::Proved);
+        assert_eq!(job.attempts, 2);
+        assert_eq!(job.cycles, Some(1000));
+        assert_eq!(job.proof_path.as_deref(), Some(dir.path()));
+        assert_eq!(store.get_by_block_id(&block_id).unwrap(), Some(job.clone()));
+        assert_eq!(store.jobs().unwrap(), vec![job]);
+    }
+
+    #[test]
+    fn job_store_delays_retries() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = JobStore::open(&dir.path().join("jobs.sqlite")).unwrap();
+        let height = BlockHeight::new(7);
+        store
+            .insert(height, &BlockId::from([7; 32]), dir.path(), "native")
+            .unwrap();
+
+        // The retried job is queued, but is not proved before the retry delay.
+        store.start(height).unwrap();
+        store
+            .fail(height, "The attempt fails", Some(Duration::from_secs(60)))
+            .unwrap();
+        let job = store.get(height).unwrap().unwrap();
+        assert_eq!(job.status, JobStatus::Queued);
+        assert_eq!(job.error.as_deref(), Some("The attempt fails"));
+        assert_eq!(store.next_queued().unwrap(), None);
+        assert_eq!(store.next_retry().unwrap(), job.not_before);
+        assert!(job.not_before.unwrap() > SystemTime::now());
+
+        // The job without attempts left fails and is not retried.
+        store.fail(height, "The attempt fails", None).unwrap();
+        let job = store.get(height).unwrap().unwrap();
+        assert_eq!(job.status, JobStatus::Failed);
+        assert_eq!(job.not_before, None);
+        assert_eq!(store.next_retry().unwrap(), None);
+
+        // The requested job is proved without the delay.
+        store.requeue(height).unwrap();
+        assert_eq!(store.next_queued().unwrap().map(|job| job.height), Some(height));
+    }
+
+    #[tokio::test]
+    async fn api_serves_requested_proofs() {
+        init_logging();
+        let node_config = NodeConfig::default();
+        let database = tempfile::tempdir().unwrap();
+        let fuel_node = start_node(&node_config, database.path()).await.unwrap();
+        let sender = transaction_sender(&fuel_node, &node_config).await;
+        // The block produced before the daemon starts is proved only on request.
+        let height = sender.send(Workload::Script).await.unwrap();
+
+        let output = tempfile::tempdir().unwrap();
+        let daemon =
+            ProverDaemon::new(&fuel_node, NativeBackend, DaemonConfig::new(output.path())).unwrap();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(api::serve(listener, daemon.handle()));
+
+        let (shutdown, shutdown_signal) = tokio::sync::oneshot::channel();
+        let request_proofs = async {
+            let client = reqwest::Client::new();
+            let response = client
+                .post(format!("{url}/prove"))
+                .json(&ProveParams { height: *height })
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), reqwest::StatusCode::ACCEPTED);
+            let job: JobResponse = response.json().await.unwrap();
+            assert_eq!(job.height, *height);
+            assert_eq!(job.backend, "native");
+
+            let block_id = hex::encode(job.block_id);
+            let proof = loop {
+                let response: ProofResponse = client
+                    .get(format!("{url}/proof/0x{block_id}"))
+                    .send()
+                    .await
+                    .unwrap()
+                    .json()
+                    .await
+                    .unwrap();
+                if let Some(proof) = response.proof {
+                    assert_eq!(response.job.status, "proved");
+                    break proof;
+                }
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            };
+            assert_eq!(proof.decoded.block_id, job.block_id);
+            assert_eq!(proof.decoded.block_height, *height);
+            assert_eq!(proof.vkey, None);
+            assert_eq!(proof.proof, "0x");
+
+            let response = client
+                .get(format!("{url}/proof/0x{}", hex::encode([0; 32])))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+            let response = client
+                .post(format!("{url}/prove"))
+                .json(&ProveParams { height: u32::MAX })
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+            let error: ErrorResponse = response.json().await.unwrap();
+            assert!(
+                error.error.contains("is not produced yet"),
+                "{}",
+                error.error
+            );
+
+            let status: StatusResponse = client
+                .get(format!("{url}/status"))
+                .send()
+                .await
+                .unwrap()
+                .json()
+                .await
+                .unwrap();
+            assert_eq!(status.backend, "native");
+            assert_eq!(status.proved, 1);
+            assert_eq!(status.failed, 0);
+            assert_eq!(status.latest_height, Some(*height));
+
+            shutdown.send(()).unwrap();
+        };
+
+        let (result, ()) = tokio::join!(
+            daemon.run(async {
+                let _ = shutdown_signal.await;
+            }),
+            request_proofs
+        );
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn api_returns_internal_errors_of_valid_requests() {
+        init_logging();
+        let database = tempfile::tempdir().unwrap();
+        let fuel_node = start_node(&NodeConfig::default(), database.path())
+            .await
+            .unwrap();
+        let output = tempfile::tempdir().unwrap();
+        let daemon =
+            ProverDaemon::new(&fuel_node, NativeBackend, DaemonConfig::new(output.path())).unwrap();
+        let handle = daemon.handle();
+        // The stopped daemon can't prove the produced block.
+        drop(daemon);
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(api::serve(listener, handle));
+
+        let response = reqwest::Client::new()
+            .post(format!("{url}/prove"))
+            .json(&ProveParams { height: 0 })
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(
+            response.status(),
+            reqwest::StatusCode::INTERNAL_SERVER_ERROR
+        );
+        let error: ErrorResponse = response.json().await.unwrap();
+        assert!(error.error.contains("is stopped"), "{}", error.error);
+    }
+
+    /// Records the phases reported by the prover.
+    #[derive(Default)]
+    struct RecordingTracker {
+        events: RefCell<Vec<(Phase, bool)>>,
+    }
+
+    impl CycleTracker for RecordingTracker {
+        fn start(&self, phase: Phase) {
+            self.events.borrow_mut().push((phase, true));
+        }
+
+        fn end(&self, phase: Phase) {
+            self.events.borrow_mut().push((phase, false));
+        }
+    }
+
+    #[tokio::test]
+    async fn prover_tracks_every_phase() {
+        init_logging();
+        let service = start_node_with_transaction_and_produce_prover_input()
+            .await
+            .unwrap();
+        let serialized_input = bincode::serialize(&service.input).unwrap();
+
+        let tracker = RecordingTracker::default();
+        let tracked = prover::prove_tracked(&serialized_input, &tracker).unwrap();
+        let untracked = prover::prove(&serialized_input).unwrap();
+        assert_eq!(
+            prover::PublicValuesStruct::abi_encode(&tracked),
+            prover::PublicValuesStruct::abi_encode(&untracked)
+        );
+
+        // Every phase is tracked once, and the phases don't overlap.
+        let events = tracker.events.into_inner();
+        assert_eq!(events.len(), Phase::ALL.len() * 2);
+        for pair in events.chunks(2) {
+            assert_eq!(pair, [(pair[0].0, true), (pair[0].0, false)]);
+        }
+        let mut phases: Vec<_> = events.iter().map(|(phase, _)| *phase).collect();
+        phases.sort();
+        phases.dedup();
+        assert_eq!(phases, Phase::ALL);
+
+        let stdout = Phase::ALL
+            .iter()
+            .map(|phase| format!("{CYCLE_REPORT_PREFIX} {} 100\n", phase.name()))
+            .collect::<String>();
+        let report = CycleReport::parse(&format!("guest output\n{stdout}"), 1000).unwrap();
+        assert_eq!(report.cycles(Phase::Execution), 100);
+        assert_eq!(report.untracked(), 500);
+        assert!(report
+            .to_string()
+            .lines()
+            .any(|line| line.starts_with("execute ")));
+        assert!(CycleReport::parse("guest output", 1000).is_err());
     }
 }
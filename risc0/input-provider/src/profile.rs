@@ -0,0 +1,119 @@
+//! The breakdown of the guest cycles by the [`Phase`]s of proving.
+//!
+//! The breakdown is per phase, not per transaction: the executor runs the whole block in
+//! the [`Phase::Execution`]. The cycles of one transaction are profiled by executing it
+//! alone in the [`prover::Mode::Transaction`].
+//!
+//! The SP1 program tracks the phases with the `cycle-tracker-report` of the SDK, and the
+//! host reads them from the `cycle_tracker` of the execution report. The RISC Zero guest
+//! counts the cycles with `env::cycle_count` when the `FUEL_CYCLE_PROFILE` environment
+//! variable is set, and prints a `cycle-tracker-report: <PHASE> <CYCLES>` line per phase
+//! that the host parses with [`CycleReport::parse`].
+
+use prover::Phase;
+use std::{
+    collections::HashMap,
+    fmt,
+};
+
+/// The environment variable that enables the profiling in the RISC Zero guest.
+pub const CYCLE_PROFILE_ENV: &str = "FUEL_CYCLE_PROFILE";
+
+/// The prefix of the lines with the cycles of the phases printed by the RISC Zero guest.
+pub const CYCLE_REPORT_PREFIX: &str = "cycle-tracker-report:";
+
+/// The cycles of the phases of one execution of the guest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleReport {
+    /// The cycles of the phases in the order of [`Phase::ALL`].
+    pub phases: Vec<(Phase, u64)>,
+    /// The cycles of the whole execution.
+    pub total: u64,
+}
+
+impl CycleReport {
+    /// Creates the report from the cycles of the phases by their names, as reported
+    /// by the `cycle_tracker` of SP1. The phases that are not tracked are skipped.
+    pub fn from_cycle_tracker(cycle_tracker: &HashMap<String, u64>, total: u64) -> Self {
+        let phases = Phase::ALL
+            .into_iter()
+            .filter_map(|phase| {
+                cycle_tracker
+                    .get(phase.name())
+                    .map(|cycles| (phase, *cycles))
+            })
+            .collect();
+        Self { phases, total }
+    }
+
+    /// Parses the report from the `stdout` of the RISC Zero guest.
+    /// The cycles of the phases reported many times are summed.
+    pub fn parse(stdout: &str, total: u64) -> anyhow::Result<Self> {
+        let mut cycle_tracker = HashMap::<String, u64>::new();
+        for line in stdout.lines() {
+            let Some(report) = line.strip_prefix(CYCLE_REPORT_PREFIX) else {
+                continue;
+            };
+            let (name, cycles) = report
+                .trim()
+                .split_once(' ')
+                .ok_or_else(|| anyhow::anyhow!("Invalid cycle report `{line}`"))?;
+            let cycles: u64 = cycles
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid cycles in `{line}`: {e}"))?;
+            *cycle_tracker.entry(name.to_string()).or_default() += cycles;
+        }
+        anyhow::ensure!(
+            !cycle_tracker.is_empty(),
+            "The guest didn't report the cycles, is `{CYCLE_PROFILE_ENV}` set?"
+        );
+        Ok(Self::from_cycle_tracker(&cycle_tracker, total))
+    }
+
+    /// Returns the cycles of the `phase`, zero if it's not tracked.
+    pub fn cycles(&self, phase: Phase) -> u64 {
+        self.phases
+            .iter()
+            .find(|(tracked, _)| *tracked == phase)
+            .map(|(_, cycles)| *cycles)
+            .unwrap_or_default()
+    }
+
+    /// Returns the cycles outside of the tracked phases, like the reading of the input
+    /// and the committing of the public values.
+    pub fn untracked(&self) -> u64 {
+        let tracked: u64 = self.phases.iter().map(|(_, cycles)| cycles).sum();
+        self.total.saturating_sub(tracked)
+    }
+}
+
+impl fmt::Display for CycleReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let percent = |cycles: u64| {
+            if self.total == 0 {
+                0.0
+            } else {
+                cycles as f64 * 100.0 / self.total as f64
+            }
+        };
+
+        writeln!(f, "{:<24} {:>14} {:>8}", "Phase", "Cycles", "Share")?;
+        for (phase, cycles) in &self.phases {
+            writeln!(
+                f,
+                "{:<24} {cycles:>14} {:>7.2}%",
+                phase.name(),
+                percent(*cycles)
+            )?;
+        }
+        let untracked = self.untracked();
+        writeln!(
+            f,
+            "{:<24} {untracked:>14} {:>7.2}%",
+            "other",
+            percent(untracked)
+        )?;
+        writeln!(f, "{:<24} {:>14}", "total", self.total)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,86 @@
+//! The local stand-in for the DA layer.
+//!
+//! The local node runs without the relayer service, so nothing is ever recorded
+//! into its relayer database. The simulation writes synthetic events directly
+//! into the database, so the blocks produced later process them.
+
+use fuel_core::database::{
+    database_description::relayer::Relayer,
+    Database,
+};
+use fuel_core_relayer::ports::RelayerDb;
+use fuel_core_types::{
+    blockchain::primitives::DaBlockHeight,
+    entities::relayer::{
+        message::MessageV1,
+        transaction::RelayedTransactionV1,
+    },
+    fuel_tx::{
+        Address,
+        Transaction,
+    },
+    fuel_types::{
+        canonical::Serialize,
+        Nonce,
+        Word,
+    },
+    services::relayer::Event,
+};
+
+/// Injects DA events into the relayer database of the local node.
+#[derive(Clone)]
+pub struct RelayerSimulation {
+    database: Database<Relayer>,
+}
+
+impl RelayerSimulation {
+    pub fn new(database: Database<Relayer>) -> Self {
+        Self { database }
+    }
+
+    /// Records `events` at the `da_height`. Heights must be inserted in increasing order,
+    /// and all events of the same height must be inserted at once.
+    pub fn insert_events(
+        &mut self,
+        da_height: DaBlockHeight,
+        events: &[Event],
+    ) -> anyhow::Result<()> {
+        self.database.insert_events(&da_height, events)?;
+        Ok(())
+    }
+
+    /// Creates the deposit of the `amount` of the base asset to the `recipient`.
+    pub fn message(
+        sender: Address,
+        recipient: Address,
+        nonce: Nonce,
+        amount: Word,
+        da_height: DaBlockHeight,
+    ) -> Event {
+        let message = MessageV1 {
+            sender,
+            recipient,
+            nonce,
+            amount,
+            data: vec![],
+            da_height,
+        };
+        Event::Message(message.into())
+    }
+
+    /// Creates the forced transaction sent from the DA layer.
+    pub fn relayed_transaction(
+        tx: &Transaction,
+        max_gas: Word,
+        nonce: Nonce,
+        da_height: DaBlockHeight,
+    ) -> Event {
+        let relayed_transaction = RelayedTransactionV1 {
+            nonce,
+            max_gas,
+            serialized_transaction: tx.to_bytes(),
+            da_height,
+        };
+        Event::Transaction(relayed_transaction.into())
+    }
+}
@@ -0,0 +1,18 @@
+use alloy_sol_types::SolType;
+use prover::{aggregate, program_id, AggregatedPublicValuesStruct};
+use risc0_zkvm::guest::env;
+
+fn main() {
+    // The image id of the guest that proves blocks and its journals.
+    let image_id: [u32; 8] = env::read();
+    let journals: Vec<Vec<u8>> = env::read();
+
+    for journal in &journals {
+        env::verify(image_id, journal).expect("Block proof verification failed");
+    }
+
+    let aggregated = aggregate(program_id(image_id), &journals).expect("Aggregation failed");
+    let bytes = AggregatedPublicValuesStruct::abi_encode(&aggregated);
+
+    env::commit_slice(&bytes);
+}
@@ -1,3 +1,24 @@
+use std::fs;
+
 fn main() {
     risc0_build::embed_methods();
+
+    // The versions are a part of the manifest stored with every proof,
+    // so the verifier can reject proofs of other builds of the guest.
+    let prover_manifest = "../prover/Cargo.toml";
+    println!("cargo:rerun-if-changed={prover_manifest}");
+    let prover_manifest: toml::Table = fs::read_to_string(prover_manifest)
+        .expect("Unable to read the prover manifest")
+        .parse()
+        .expect("Unable to parse the prover manifest");
+
+    let prover_version = prover_manifest["package"]["version"]
+        .as_str()
+        .expect("The prover version is a string");
+    let fuel_core_version = prover_manifest["dependencies"]["fuel-core-executor"]["version"]
+        .as_str()
+        .expect("The fuel-core version is a string");
+
+    println!("cargo:rustc-env=PROVER_VERSION={prover_version}");
+    println!("cargo:rustc-env=FUEL_CORE_VERSION={fuel_core_version}");
 }
@@ -1,11 +1,35 @@
-use alloy_sol_types::SolType;
-use prover::{prove, PublicValuesStruct};
+use prover::{CycleTracker, Mode, Phase};
 use risc0_zkvm::guest::env;
+use std::cell::Cell;
+
+/// Prints the cycles of every phase for the host, see `input_provider::profile`.
+#[derive(Default)]
+struct CycleProfiler {
+    start: Cell<u64>,
+}
+
+impl CycleTracker for CycleProfiler {
+    fn start(&self, _: Phase) {
+        self.start.set(env::cycle_count());
+    }
+
+    fn end(&self, phase: Phase) {
+        let cycles = env::cycle_count() - self.start.get();
+        println!("cycle-tracker-report: {} {cycles}", phase.name());
+    }
+}
 
 fn main() {
+    let mode: Mode = env::read();
     let bytes: Vec<u8> = env::read();
-    let proof = prove(&bytes).expect("Proof generation failed");
-    let bytes = PublicValuesStruct::abi_encode(&proof);
+    // The phases are profiled only on request, so the proved execution doesn't print.
+    let bytes = if std::env::var_os("FUEL_CYCLE_PROFILE").is_some() {
+        mode.prove_tracked(&bytes, &CycleProfiler::default())
+    } else {
+        mode.prove(&bytes)
+    }
+    .expect("Proof generation failed");
 
-    env::commit(&bytes);
+    // The journal is the ABI encoded `PublicValuesStruct`, so it can be decoded on-chain.
+    env::commit_slice(&bytes);
 }
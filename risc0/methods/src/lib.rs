@@ -1 +1,7 @@
 include!(concat!(env!("OUT_DIR"), "/methods.rs"));
+
+/// The version of the `prover` crate the guests are built with.
+pub const PROVER_VERSION: &str = env!("PROVER_VERSION");
+
+/// The version of `fuel-core` the guests are built with.
+pub const FUEL_CORE_VERSION: &str = env!("FUEL_CORE_VERSION");
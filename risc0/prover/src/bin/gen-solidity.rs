@@ -0,0 +1,26 @@
+//! Generates the Solidity library decoding the public values committed by the guests.
+//!
+//! The contracts import the library from `contracts/src/PublicValues.sol`, regenerate it
+//! after changing `prover/src/public_values.sol`:
+//! ```shell
+//! cargo run -p prover --bin gen-solidity
+//! ```
+//! Another path to write the library to can be passed as the argument.
+
+use std::path::PathBuf;
+
+/// The library imported by the contracts.
+const SOLIDITY_LIBRARY: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../contracts/src/PublicValues.sol"
+);
+
+fn main() -> std::io::Result<()> {
+    let path = std::env::args_os()
+        .nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(SOLIDITY_LIBRARY));
+    std::fs::write(&path, prover::solidity_library())?;
+    println!("Saved the Solidity library to {}", path.display());
+    Ok(())
+}
@@ -0,0 +1,40 @@
+//! A program that verifies compressed proofs of consecutive blocks and commits
+//! the aggregated public values, so one proof covers all blocks.
+
+// These two lines are necessary for the program to properly compile.
+//
+// Under the hood, we wrap your main function with some extra code so that it behaves properly
+// inside the zkVM.
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use alloy_sol_types::SolType;
+use prover::{
+    aggregate,
+    program_id,
+    AggregatedPublicValuesStruct,
+};
+use sha2::{
+    Digest,
+    Sha256,
+};
+
+pub fn main() {
+    // The verifying key hash of the program that proves blocks and its public values.
+    let vkey = sp1_zkvm::io::read::<[u32; 8]>();
+    let public_values = sp1_zkvm::io::read::<Vec<Vec<u8>>>();
+
+    // Verify the proofs. The proofs themselves are provided by the prover
+    // with `SP1Stdin::write_proof` and are checked against the digest of the public values.
+    for public_values in &public_values {
+        let public_values_digest = Sha256::digest(public_values);
+        sp1_zkvm::lib::verify::verify_sp1_proof(&vkey, &public_values_digest.into());
+    }
+
+    let aggregated =
+        aggregate(program_id(vkey), &public_values).expect("Aggregation failed");
+
+    // Commit to the aggregated public values of the program.
+    let bytes = AggregatedPublicValuesStruct::abi_encode(&aggregated);
+    sp1_zkvm::io::commit_slice(&bytes);
+}
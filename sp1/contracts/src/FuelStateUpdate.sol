@@ -0,0 +1,110 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+import {
+    AggregatedPublicValuesStruct,
+    PublicValues,
+    PublicValuesStruct
+} from "./PublicValues.sol";
+
+/// @notice Verifies the proof of the program identified by the `programKey`.
+/// @dev The interface is the same as `ISP1Verifier`, so the SP1 verifier gateway
+/// can be used directly. RISC Zero proofs are verified with `RiscZeroProofVerifier`.
+interface IFuelProofVerifier {
+    function verifyProof(
+        bytes32 programKey,
+        bytes calldata publicValues,
+        bytes calldata proofBytes
+    ) external view;
+}
+
+/// @notice Settles the Fuel chain on L1 by accepting the proofs of consecutive blocks.
+contract FuelStateUpdate {
+    /// @notice The verifier of the block proofs.
+    IFuelProofVerifier public immutable verifier;
+    /// @notice The verification key hash (SP1) or the image id (RISC Zero) of the prover.
+    bytes32 public immutable programKey;
+    /// @notice The verification key hash (SP1) or the image id (RISC Zero) of the aggregation
+    /// program.
+    bytes32 public immutable aggregationProgramKey;
+    /// @notice The id of the prover committed by the aggregation program, see `prover::program_id`.
+    bytes32 public immutable programId;
+    /// @notice The id of the latest settled block.
+    bytes32 public blockId;
+    /// @notice The height of the latest settled block.
+    uint32 public blockHeight;
+
+    /// @notice Emitted when the block is settled.
+    event StateUpdated(bytes32 indexed blockId, uint32 blockHeight, bytes32 messageOutboxRoot);
+
+    /// @notice The proven block is not a child of the latest settled block.
+    error UnexpectedParentBlock(bytes32 expected, bytes32 actual);
+    /// @notice The proven block doesn't follow the latest settled block.
+    error UnexpectedBlockHeight(uint32 expected, uint32 actual);
+    /// @notice The aggregated proofs are not the proofs of the prover.
+    error UnexpectedProgram(bytes32 expected, bytes32 actual);
+
+    constructor(
+        IFuelProofVerifier _verifier,
+        bytes32 _programKey,
+        bytes32 _aggregationProgramKey,
+        bytes32 _programId,
+        bytes32 _blockId,
+        uint32 _blockHeight
+    ) {
+        verifier = _verifier;
+        programKey = _programKey;
+        aggregationProgramKey = _aggregationProgramKey;
+        programId = _programId;
+        blockId = _blockId;
+        blockHeight = _blockHeight;
+    }
+
+    /// @notice Settles the block proven by the `proofBytes` with the `publicValues`.
+    /// Gaps and replays are rejected, because the block must be a child of the latest one.
+    function updateState(bytes calldata publicValues, bytes calldata proofBytes) external {
+        verifier.verifyProof(programKey, publicValues, proofBytes);
+        PublicValuesStruct memory values = PublicValues.decodePublicValues(publicValues);
+        checkParent(bytes32(values.parent_block_id), values.block_height);
+
+        blockId = bytes32(values.block_id);
+        blockHeight = values.block_height;
+        emit StateUpdated(blockId, blockHeight, values.message_outbox_root);
+    }
+
+    /// @notice Settles the consecutive blocks proven by the aggregated proof.
+    /// The aggregation program verifies the block proofs against the program id from its input,
+    /// so the committed `program_id` must be the one of the prover.
+    function updateAggregatedState(bytes calldata publicValues, bytes calldata proofBytes)
+        external
+    {
+        verifier.verifyProof(aggregationProgramKey, publicValues, proofBytes);
+        AggregatedPublicValuesStruct memory values =
+            PublicValues.decodeAggregatedPublicValues(publicValues);
+
+        if (values.program_id != programId) {
+            revert UnexpectedProgram(programId, values.program_id);
+        }
+        checkParent(bytes32(values.parent_block_id), values.first_block_height);
+
+        for (uint256 i = 0; i < values.block_ids.length; i++) {
+            emit StateUpdated(
+                values.block_ids[i],
+                values.first_block_height + uint32(i),
+                values.message_outbox_roots[i]
+            );
+        }
+        blockId = bytes32(values.block_id);
+        blockHeight = values.last_block_height;
+    }
+
+    /// @notice Rejects the block unless it is the child of the latest settled block.
+    function checkParent(bytes32 parentBlockId, uint32 height) internal view {
+        if (parentBlockId != blockId) {
+            revert UnexpectedParentBlock(blockId, parentBlockId);
+        }
+        if (height != blockHeight + 1) {
+            revert UnexpectedBlockHeight(blockHeight + 1, height);
+        }
+    }
+}
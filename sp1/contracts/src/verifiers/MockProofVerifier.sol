@@ -0,0 +1,16 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+import {IFuelProofVerifier} from "../FuelStateUpdate.sol";
+
+/// @notice Accepts any non-empty proof, for the proofs generated in the development mode
+/// and for testing the settlement without the Groth16 verifier contracts.
+contract MockProofVerifier is IFuelProofVerifier {
+    error EmptyProof();
+
+    function verifyProof(bytes32, bytes calldata, bytes calldata proofBytes) external pure {
+        if (proofBytes.length == 0) {
+            revert EmptyProof();
+        }
+    }
+}
@@ -0,0 +1,12 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+import {ControlID} from "risc0/groth16/ControlID.sol";
+import {RiscZeroGroth16Verifier} from "risc0/groth16/RiscZeroGroth16Verifier.sol";
+
+/// @notice The Groth16 verifier of RISC Zero with the control ids of the `risc0-ethereum`
+/// release installed into `lib`, so the settlement harness verifies the receipts of the host.
+/// On-chain, `RiscZeroProofVerifier` wraps the deployed RISC Zero verifier router instead.
+contract RiscZeroGroth16 is RiscZeroGroth16Verifier {
+    constructor() RiscZeroGroth16Verifier(ControlID.CONTROL_ROOT, ControlID.BN254_CONTROL_ID) {}
+}
@@ -0,0 +1,27 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+import {IFuelProofVerifier} from "../FuelStateUpdate.sol";
+
+/// @notice The verifier interface of the RISC Zero Ethereum contracts.
+interface IRiscZeroVerifier {
+    function verify(bytes calldata seal, bytes32 imageId, bytes32 journalDigest) external view;
+}
+
+/// @notice Verifies the RISC Zero proofs, where the `programKey` is the image id,
+/// the `publicValues` are the journal and the `proofBytes` are the encoded seal.
+contract RiscZeroProofVerifier is IFuelProofVerifier {
+    IRiscZeroVerifier public immutable verifier;
+
+    constructor(IRiscZeroVerifier _verifier) {
+        verifier = _verifier;
+    }
+
+    function verifyProof(
+        bytes32 programKey,
+        bytes calldata publicValues,
+        bytes calldata proofBytes
+    ) external view {
+        verifier.verify(proofBytes, programKey, sha256(publicValues));
+    }
+}
@@ -0,0 +1,250 @@
+//! The HTTP API of the prover daemon.
+//!
+//! Other services request and fetch the proofs without linking the zkVM SDKs:
+//! - `POST /prove` with `{"height": <HEIGHT>}` queues the proof of the block at the height
+//!   and returns its [`JobResponse`];
+//! - `GET /proof/<BLOCK_ID>` returns the [`ProofResponse`] with the job of the block and,
+//!   once the block is proved, its public values, proof and verification key;
+//! - `GET /status` returns the [`StatusResponse`] with the backend and the number of jobs.
+//!
+//! The errors are returned as `{"error": <MESSAGE>}`.
+
+use crate::{
+    daemon::{
+        DaemonHandle,
+        ProvedBlock,
+        UnproducedBlock,
+    },
+    fixture::DecodedPublicValues,
+    job_store::{
+        Job,
+        JobStatus,
+    },
+};
+use axum::{
+    extract::{
+        Path,
+        State,
+    },
+    http::StatusCode,
+    response::{
+        IntoResponse,
+        Response,
+    },
+    routing::{
+        get,
+        post,
+    },
+    Json,
+    Router,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use tokio::net::TcpListener;
+
+/// The body of the `POST /prove` request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProveParams {
+    pub height: u32,
+}
+
+/// The proving job of the block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobResponse {
+    pub height: u32,
+    pub block_id: [u8; 32],
+    pub backend: String,
+    /// One of `queued`, `proving`, `proved` or `failed`.
+    pub status: String,
+    pub attempts: u32,
+    pub cycles: Option<u64>,
+    /// The error of the last failed attempt.
+    pub error: Option<String>,
+}
+
+impl From<Job> for JobResponse {
+    fn from(job: Job) -> Self {
+        Self {
+            height: *job.height,
+            block_id: job.block_id.into(),
+            backend: job.backend,
+            status: job.status.to_string(),
+            attempts: job.attempts,
+            cycles: job.cycles,
+            error: job.error,
+        }
+    }
+}
+
+/// The proof of the block with its decoded public values.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockProofResponse {
+    #[serde(flatten)]
+    pub decoded: DecodedPublicValues,
+    /// The key of the program that verifies the proof, if the backend has one.
+    pub vkey: Option<String>,
+    /// The ABI encoded `PublicValuesStruct`.
+    pub public_values: String,
+    /// The proof in the format of the backend.
+    pub proof: String,
+}
+
+/// The response of the `GET /proof/<BLOCK_ID>` request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofResponse {
+    pub job: JobResponse,
+    /// The proof, `None` until the block is proved.
+    pub proof: Option<BlockProofResponse>,
+}
+
+/// The response of the `GET /status` request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusResponse {
+    pub backend: String,
+    pub vkey: Option<String>,
+    /// The height of the latest block with a job.
+    pub latest_height: Option<u32>,
+    pub queued: u64,
+    pub proving: u64,
+    pub proved: u64,
+    pub failed: u64,
+}
+
+/// The body of the error response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorResponse {
+    pub error: String,
+}
+
+struct ApiError {
+    status: StatusCode,
+    error: anyhow::Error,
+}
+
+impl ApiError {
+    fn bad_request(error: anyhow::Error) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            error,
+        }
+    }
+
+    fn not_found(error: anyhow::Error) -> Self {
+        Self {
+            status: StatusCode::NOT_FOUND,
+            error,
+        }
+    }
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(error: anyhow::Error) -> Self {
+        Self {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            error,
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = ErrorResponse {
+            error: self.error.to_string(),
+        };
+        (self.status, Json(body)).into_response()
+    }
+}
+
+/// Returns the routes of the API served by the daemon with the `handle`.
+pub fn router(handle: DaemonHandle) -> Router {
+    Router::new()
+        .route("/prove", post(prove))
+        .route("/proof/:block_id", get(proof))
+        .route("/status", get(status))
+        .with_state(handle)
+}
+
+/// Serves the API on the `listener` until the task is dropped.
+pub async fn serve(listener: TcpListener, handle: DaemonHandle) -> anyhow::Result<()> {
+    tracing::info!("The API is listening on {}", listener.local_addr()?);
+    axum::serve(listener, router(handle)).await?;
+    Ok(())
+}
+
+async fn prove(
+    State(handle): State<DaemonHandle>,
+    Json(params): Json<ProveParams>,
+) -> Result<(StatusCode, Json<JobResponse>), ApiError> {
+    // Only the requests of unproduced blocks are invalid, other errors are internal.
+    let job = handle.prove(params.height.into()).await.map_err(|e| {
+        if e.is::<UnproducedBlock>() {
+            ApiError::bad_request(e)
+        } else {
+            ApiError::from(e)
+        }
+    })?;
+    Ok((StatusCode::ACCEPTED, Json(job.into())))
+}
+
+async fn proof(
+    State(handle): State<DaemonHandle>,
+    Path(block_id): Path<String>,
+) -> Result<Json<ProofResponse>, ApiError> {
+    let block_id: [u8; 32] = hex::decode(block_id.trim_start_matches("0x"))
+        .ok()
+        .and_then(|block_id| block_id.try_into().ok())
+        .ok_or_else(|| {
+            ApiError::bad_request(anyhow::anyhow!("The block id must be 32 bytes in hex"))
+        })?;
+    let job = handle
+        .store()
+        .get_by_block_id(&block_id.into())?
+        .ok_or_else(|| {
+            ApiError::not_found(anyhow::anyhow!(
+                "The block 0x{} is not requested",
+                hex::encode(block_id)
+            ))
+        })?;
+
+    let proved_block = if job.status == JobStatus::Proved {
+        ProvedBlock::load(handle.output_dir(), job.height)?
+    } else {
+        None
+    };
+    let proof = proved_block
+        .map(|proved_block| {
+            let public_values = &proved_block.proof.public_values;
+            Ok::<_, anyhow::Error>(BlockProofResponse {
+                decoded: DecodedPublicValues::decode(public_values)?,
+                vkey: handle.vkey().map(ToString::to_string),
+                public_values: format!("0x{}", hex::encode(public_values)),
+                proof: format!("0x{}", hex::encode(&proved_block.proof.proof)),
+            })
+        })
+        .transpose()?;
+
+    Ok(Json(ProofResponse {
+        job: job.into(),
+        proof,
+    }))
+}
+
+async fn status(
+    State(handle): State<DaemonHandle>,
+) -> Result<Json<StatusResponse>, ApiError> {
+    let store = handle.store();
+    Ok(Json(StatusResponse {
+        backend: handle.backend().to_string(),
+        vkey: handle.vkey().map(ToString::to_string),
+        latest_height: store.latest_height()?.map(|height| *height),
+        queued: store.count(JobStatus::Queued)?,
+        proving: store.count(JobStatus::Proving)?,
+        proved: store.count(JobStatus::Proved)?,
+        failed: store.count(JobStatus::Failed)?,
+    }))
+}
@@ -0,0 +1,349 @@
+//! The persistent store of the proving jobs of the prover daemon.
+//!
+//! Every block followed by the daemon has a [`Job`] in the SQLite database. The job records
+//! the id of the block, where its input is saved, which backend proves it, the status of
+//! proving, the number of attempts, the cycles of the proof, where the proof is saved, the
+//! last error and the time before which the retry of the failed job is not proved.
+//! The jobs interrupted by a restart are queued again when the store is opened.
+
+use fuel_core_types::{
+    blockchain::primitives::BlockId,
+    fuel_types::BlockHeight,
+};
+use rusqlite::{
+    params,
+    Connection,
+    OptionalExtension,
+    Row,
+};
+use std::{
+    fmt,
+    path::{
+        Path,
+        PathBuf,
+    },
+    str::FromStr,
+    sync::Mutex,
+    time::{
+        Duration,
+        SystemTime,
+        UNIX_EPOCH,
+    },
+};
+
+/// The status of the proving job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobStatus {
+    /// The job waits for the prover.
+    Queued,
+    /// The block is being proved.
+    Proving,
+    /// The block is proved.
+    Proved,
+    /// The block is not proved after all attempts.
+    Failed,
+}
+
+impl JobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobStatus::Queued => "queued",
+            JobStatus::Proving => "proving",
+            JobStatus::Proved => "proved",
+            JobStatus::Failed => "failed",
+        }
+    }
+}
+
+impl fmt::Display for JobStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for JobStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "queued" => Ok(JobStatus::Queued),
+            "proving" => Ok(JobStatus::Proving),
+            "proved" => Ok(JobStatus::Proved),
+            "failed" => Ok(JobStatus::Failed),
+            _ => Err(anyhow::anyhow!("Unknown job status {s}")),
+        }
+    }
+}
+
+/// The job to prove the block at the `height`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Job {
+    pub height: BlockHeight,
+    pub block_id: BlockId,
+    /// The path of the bincode encoded [`prover::Input`].
+    pub input_path: PathBuf,
+    /// The name of the backend proving the block.
+    pub backend: String,
+    pub status: JobStatus,
+    pub attempts: u32,
+    /// The cycles of the proof, if the backend reports them.
+    pub cycles: Option<u64>,
+    /// The directory of the proved block.
+    pub proof_path: Option<PathBuf>,
+    /// The error of the last failed attempt.
+    pub error: Option<String>,
+    /// The time before which the queued job is not proved, set by the retry delay.
+    pub not_before: Option<SystemTime>,
+}
+
+impl Job {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        let status: String = row.get("status")?;
+        let status = status.parse().map_err(|e: anyhow::Error| {
+            rusqlite::Error::FromSqlConversionFailure(
+                0,
+                rusqlite::types::Type::Text,
+                e.into(),
+            )
+        })?;
+        Ok(Self {
+            height: row.get::<_, u32>("height")?.into(),
+            block_id: row.get::<_, [u8; 32]>("block_id")?.into(),
+            input_path: row.get::<_, String>("input_path")?.into(),
+            backend: row.get("backend")?,
+            status,
+            attempts: row.get("attempts")?,
+            cycles: row.get("cycles")?,
+            proof_path: row.get::<_, Option<String>>("proof_path")?.map(Into::into),
+            error: row.get("error")?,
+            not_before: row
+                .get::<_, Option<i64>>("not_before")?
+                .map(from_unix_millis),
+        })
+    }
+}
+
+/// The SQLite database of the proving jobs.
+pub struct JobStore {
+    connection: Mutex<Connection>,
+}
+
+impl JobStore {
+    /// Opens the store at the `path`, creating it if it doesn't exist.
+    /// The jobs interrupted while proving are queued again.
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        let connection = Connection::open(path)?;
+        connection.execute_batch(
+            "CREATE TABLE IF NOT EXISTS jobs (
+                height INTEGER PRIMARY KEY,
+                block_id BLOB NOT NULL UNIQUE,
+                input_path TEXT NOT NULL,
+                backend TEXT NOT NULL,
+                status TEXT NOT NULL,
+                attempts INTEGER NOT NULL DEFAULT 0,
+                cycles INTEGER,
+                proof_path TEXT,
+                error TEXT,
+                not_before INTEGER
+            );",
+        )?;
+        connection.execute(
+            "UPDATE jobs SET status = ?1 WHERE status = ?2",
+            params![JobStatus::Queued.as_str(), JobStatus::Proving.as_str()],
+        )?;
+
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    fn connection(&self) -> std::sync::MutexGuard<'_, Connection> {
+        self.connection.lock().expect("The job store is poisoned")
+    }
+
+    /// Queues the job to prove the block at the `height` with the `backend`.
+    /// Does nothing if the job already exists.
+    pub fn insert(
+        &self,
+        height: BlockHeight,
+        block_id: &BlockId,
+        input_path: &Path,
+        backend: &str,
+    ) -> anyhow::Result<()> {
+        self.connection().execute(
+            "INSERT OR IGNORE INTO jobs (height, block_id, input_path, backend, status)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                *height,
+                <[u8; 32]>::from(*block_id),
+                input_path.to_string_lossy(),
+                backend,
+                JobStatus::Queued.as_str()
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Returns the job at the `height`.
+    pub fn get(&self, height: BlockHeight) -> anyhow::Result<Option<Job>> {
+        let job = self
+            .connection()
+            .query_row(
+                "SELECT * FROM jobs WHERE height = ?1",
+                params![*height],
+                Job::from_row,
+            )
+            .optional()?;
+        Ok(job)
+    }
+
+    /// Returns the job of the block with the `block_id`.
+    pub fn get_by_block_id(&self, block_id: &BlockId) -> anyhow::Result<Option<Job>> {
+        let job = self
+            .connection()
+            .query_row(
+                "SELECT * FROM jobs WHERE block_id = ?1",
+                params![<[u8; 32]>::from(*block_id)],
+                Job::from_row,
+            )
+            .optional()?;
+        Ok(job)
+    }
+
+    /// Returns all jobs ordered by the height.
+    pub fn jobs(&self) -> anyhow::Result<Vec<Job>> {
+        let connection = self.connection();
+        let mut statement = connection.prepare("SELECT * FROM jobs ORDER BY height")?;
+        let jobs = statement
+            .query_map([], Job::from_row)?
+            .collect::<Result<_, _>>()?;
+        Ok(jobs)
+    }
+
+    /// Returns the queued job with the lowest height that can be proved now.
+    pub fn next_queued(&self) -> anyhow::Result<Option<Job>> {
+        let job = self
+            .connection()
+            .query_row(
+                "SELECT * FROM jobs
+                 WHERE status = ?1 AND (not_before IS NULL OR not_before <= ?2)
+                 ORDER BY height LIMIT 1",
+                params![JobStatus::Queued.as_str(), unix_millis(SystemTime::now())],
+                Job::from_row,
+            )
+            .optional()?;
+        Ok(job)
+    }
+
+    /// Returns the earliest time when a queued job delayed by a retry can be proved.
+    pub fn next_retry(&self) -> anyhow::Result<Option<SystemTime>> {
+        let not_before: Option<i64> = self.connection().query_row(
+            "SELECT MIN(not_before) FROM jobs WHERE status = ?1 AND not_before > ?2",
+            params![JobStatus::Queued.as_str(), unix_millis(SystemTime::now())],
+            |row| row.get(0),
+        )?;
+        Ok(not_before.map(from_unix_millis))
+    }
+
+    /// Returns the number of jobs with the `status`.
+    pub fn count(&self, status: JobStatus) -> anyhow::Result<u64> {
+        let count = self.connection().query_row(
+            "SELECT COUNT(*) FROM jobs WHERE status = ?1",
+            params![status.as_str()],
+            |row| row.get(0),
+        )?;
+        Ok(count)
+    }
+
+    /// Returns the height of the latest block with a job.
+    pub fn latest_height(&self) -> anyhow::Result<Option<BlockHeight>> {
+        let height: Option<u32> =
+            self.connection()
+                .query_row("SELECT MAX(height) FROM jobs", [], |row| row.get(0))?;
+        Ok(height.map(Into::into))
+    }
+
+    /// Marks the job at the `height` as proving and counts the attempt.
+    pub fn start(&self, height: BlockHeight) -> anyhow::Result<()> {
+        self.connection().execute(
+            "UPDATE jobs SET status = ?1, attempts = attempts + 1 WHERE height = ?2",
+            params![JobStatus::Proving.as_str(), *height],
+        )?;
+        Ok(())
+    }
+
+    /// Marks the job at the `height` as proved with the proof saved at the `proof_path`.
+    pub fn complete(
+        &self,
+        height: BlockHeight,
+        proof_path: &Path,
+        cycles: Option<u64>,
+    ) -> anyhow::Result<()> {
+        self.connection().execute(
+            "UPDATE jobs SET status = ?1, proof_path = ?2, cycles = ?3, error = NULL
+             WHERE height = ?4",
+            params![
+                JobStatus::Proved.as_str(),
+                proof_path.to_string_lossy(),
+                cycles,
+                *height
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Records the `error` of the job at the `height`. The job is queued again
+    /// to be proved after the `retry_delay` if it is retried, otherwise it fails.
+    pub fn fail(
+        &self,
+        height: BlockHeight,
+        error: &str,
+        retry_delay: Option<Duration>,
+    ) -> anyhow::Result<()> {
+        let (status, not_before) = match retry_delay {
+            Some(retry_delay) => {
+                let not_before = SystemTime::now()
+                    .checked_add(retry_delay)
+                    .map_or(i64::MAX, unix_millis);
+                (JobStatus::Queued, Some(not_before))
+            }
+            None => (JobStatus::Failed, None),
+        };
+        self.connection().execute(
+            "UPDATE jobs SET status = ?1, error = ?2, not_before = ?3 WHERE height = ?4",
+            params![status.as_str(), error, not_before, *height],
+        )?;
+        Ok(())
+    }
+
+    /// Queues the failed job at the `height` again with new attempts.
+    pub fn requeue(&self, height: BlockHeight) -> anyhow::Result<()> {
+        self.connection().execute(
+            "UPDATE jobs SET status = ?1, attempts = 0, not_before = NULL
+             WHERE height = ?2 AND status = ?3",
+            params![
+                JobStatus::Queued.as_str(),
+                *height,
+                JobStatus::Failed.as_str()
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+/// Converts the `time` into the milliseconds since the Unix epoch stored in the database.
+fn unix_millis(time: SystemTime) -> i64 {
+    let millis = time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    i64::try_from(millis).unwrap_or(i64::MAX)
+}
+
+/// Converts the milliseconds since the Unix epoch stored in the database into the time.
+fn from_unix_millis(millis: i64) -> SystemTime {
+    let millis = u64::try_from(millis).unwrap_or_default();
+    UNIX_EPOCH
+        .checked_add(Duration::from_millis(millis))
+        .unwrap_or(UNIX_EPOCH)
+}
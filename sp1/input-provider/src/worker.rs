@@ -0,0 +1,97 @@
+//! The worker that proves the blocks for the remote [`crate::coordinator::Coordinator`].
+
+use crate::{
+    coordinator::{
+        read_messages,
+        write_message,
+        CoordinatorMessage,
+        WorkerMessage,
+        HEARTBEAT_INTERVAL,
+    },
+    daemon::{
+        BlockProof,
+        ProvingBackend,
+    },
+};
+use std::sync::Arc;
+use tokio::{
+    net::{
+        TcpStream,
+        ToSocketAddrs,
+    },
+    task::JoinHandle,
+};
+
+/// Proves the blocks received from the coordinator with the backend.
+pub struct ProvingWorker<B> {
+    backend: Arc<B>,
+}
+
+impl<B> ProvingWorker<B>
+where
+    B: ProvingBackend,
+{
+    pub fn new(backend: B) -> Self {
+        Self {
+            backend: Arc::new(backend),
+        }
+    }
+
+    /// Connects to the coordinator at the `address` and proves the blocks one by one
+    /// until the coordinator disconnects. Returns an error if the worker is rejected.
+    pub async fn run(&self, address: impl ToSocketAddrs) -> anyhow::Result<()> {
+        let stream = TcpStream::connect(address).await?;
+        let (reader, mut writer) = stream.into_split();
+        let mut messages = read_messages::<CoordinatorMessage>(reader);
+
+        let register = WorkerMessage::Register {
+            backend: self.backend.name().to_string(),
+            vkey: self.backend.vkey(),
+        };
+        write_message(&mut writer, &register).await?;
+        match messages.recv().await.transpose()? {
+            Some(CoordinatorMessage::Accepted) => {}
+            Some(CoordinatorMessage::Rejected(reason)) => {
+                anyhow::bail!("The coordinator rejected the worker: {reason}")
+            }
+            message => anyhow::bail!("Expected the registration, got {message:?}"),
+        }
+        tracing::info!("The worker is registered in the coordinator");
+
+        let mut heartbeat = tokio::time::interval(HEARTBEAT_INTERVAL);
+        let mut proving: Option<JoinHandle<anyhow::Result<BlockProof>>> = None;
+        loop {
+            tokio::select! {
+                _ = heartbeat.tick() => {
+                    write_message(&mut writer, &WorkerMessage::Heartbeat).await?;
+                }
+                message = messages.recv(), if proving.is_none() => {
+                    match message.transpose() {
+                        Ok(Some(CoordinatorMessage::Prove(input))) => {
+                            let backend = self.backend.clone();
+                            proving = Some(tokio::task::spawn_blocking(move || {
+                                backend.prove(&input)
+                            }));
+                        }
+                        Ok(Some(message)) => anyhow::bail!("Unexpected message {message:?}"),
+                        // The coordinator is stopped.
+                        Ok(None) | Err(_) => return Ok(()),
+                    }
+                }
+                result = async { proving.as_mut().expect("The worker is proving").await },
+                    if proving.is_some() =>
+                {
+                    proving = None;
+                    let result = result
+                        .map_err(|e| anyhow::anyhow!("The backend panicked: {e}"))
+                        .and_then(|result| result)
+                        .map_err(|e| e.to_string());
+                    if let Err(e) = &result {
+                        tracing::warn!("Unable to prove the block: {e}");
+                    }
+                    write_message(&mut writer, &WorkerMessage::Proved(result)).await?;
+                }
+            }
+        }
+    }
+}
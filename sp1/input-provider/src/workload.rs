@@ -0,0 +1,721 @@
+//! Workloads of transactions used to produce blocks for the prover.
+//!
+//! Each workload sends the transactions of one kind to the node and returns the height
+//! of the block that includes them. Some workloads require additional setup
+//! of the chain(like the privileged address) or the genesis state(like coins of
+//! different assets), see [`Workload::prepare`].
+
+use crate::NodeConfig;
+use fuel_core::{
+    chain_config::{
+        CoinConfig,
+        MessageConfig,
+    },
+    combined_database::CombinedDatabase,
+    service::adapters::PoAAdapter,
+};
+use fuel_core_client::client::{
+    pagination::{
+        PageDirection,
+        PaginationRequest,
+    },
+    types::{
+        Coin,
+        TransactionStatus,
+    },
+    FuelClient,
+};
+use fuel_core_poa::service::Mode;
+use fuel_core_storage::transactional::HistoricalView;
+use fuel_core_types::{
+    blockchain::primitives::DaBlockHeight,
+    fuel_asm::{
+        op,
+        GTFArgs,
+        RegId,
+    },
+    fuel_crypto::{
+        Hasher,
+        SecretKey,
+    },
+    fuel_tx::{
+        Address,
+        AssetId,
+        BlobBody,
+        BlobId,
+        Buildable,
+        Bytes32,
+        ConsensusParameters,
+        Contract,
+        ContractId,
+        Input,
+        Output,
+        Salt,
+        Transaction,
+        TransactionBuilder,
+        TxId,
+        TxPointer,
+        UniqueIdentifier,
+        UpgradePurpose,
+        UploadBody,
+        UploadSubsection,
+        UtxoId,
+    },
+    fuel_types::{
+        BlockHeight,
+        Nonce,
+        Word,
+    },
+    tai64::Tai64,
+};
+use fuels::{
+    accounts::Account,
+    prelude::WalletUnlocked,
+};
+use fuels_core::types::transaction_builders::{
+    BuildableTransaction,
+    ScriptTransactionBuilder,
+};
+use futures::StreamExt;
+use std::{
+    fmt,
+    str::FromStr,
+    time::Duration,
+};
+
+/// The maximum fee that workload transactions are willing to pay.
+const MAX_FEE: Word = 1_000_000;
+/// The gas limit of the workload scripts.
+const SCRIPT_GAS_LIMIT: Word = 100_000;
+/// The amount transferred between accounts by workloads.
+const TRANSFER_AMOUNT: Word = 10_000_000;
+
+/// The assets minted at genesis for the [`Workload::MultiAssetTransfer`].
+const MULTI_ASSET_IDS: [AssetId; 3] = [
+    AssetId::new([1; 32]),
+    AssetId::new([2; 32]),
+    AssetId::new([3; 32]),
+];
+/// The nonce of the message created at genesis for the [`Workload::MessageSpend`].
+const MESSAGE_NONCE: Nonce = Nonce::new([1; 32]);
+/// The size of the bytecode uploaded by the [`Workload::Upload`].
+const UPLOAD_BYTECODE_SIZE: usize = 1024;
+/// The amount withdrawn to the DA layer by the [`Workload::Withdrawal`].
+/// It fits into the immediate value of the `MOVI` instruction.
+const WITHDRAWAL_AMOUNT: u32 = 1_000;
+/// The recipient of the message on the DA layer for the [`Workload::Withdrawal`].
+const WITHDRAWAL_RECIPIENT: Address = Address::new([0xee; 32]);
+/// The time of the genesis block. Manually produced blocks
+/// have the timestamp equal to this time plus the block height in seconds.
+const DETERMINISTIC_GENESIS_TIME: Tai64 = Tai64::UNIX_EPOCH;
+
+/// The kind of transactions included into the block for the prover.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Workload {
+    /// The script that runs the loop with arithmetic operations.
+    #[default]
+    Script,
+    /// The deployment of the contract with the `Create` transaction.
+    ContractDeployment,
+    /// The script that calls the previously deployed contract.
+    ContractCall,
+    /// The spending of the coin owned by the predicate.
+    PredicateSpend,
+    /// The spending of the message coin from the DA layer.
+    MessageSpend,
+    /// The transfer of coins of several assets in one transaction.
+    MultiAssetTransfer,
+    /// The upload of the bytecode subsection by the privileged address.
+    Upload,
+    /// The upgrade of the consensus parameters by the privileged address.
+    Upgrade,
+    /// The upload of the blob.
+    Blob,
+    /// The script that sends the message with coins to the DA layer.
+    Withdrawal,
+}
+
+impl Workload {
+    /// All available workloads.
+    pub const ALL: [Workload; 10] = [
+        Workload::Script,
+        Workload::ContractDeployment,
+        Workload::ContractCall,
+        Workload::PredicateSpend,
+        Workload::MessageSpend,
+        Workload::MultiAssetTransfer,
+        Workload::Upload,
+        Workload::Upgrade,
+        Workload::Blob,
+        Workload::Withdrawal,
+    ];
+
+    /// Prepares the chain config and the state config required by the workload.
+    pub fn prepare(&self, node_config: NodeConfig) -> anyhow::Result<NodeConfig> {
+        let owner = Input::owner(&node_config.wallet_secret.public_key());
+
+        match self {
+            Workload::MessageSpend => {
+                let mut state_config = node_config.state_config()?;
+                state_config.messages.push(MessageConfig {
+                    sender: Address::zeroed(),
+                    recipient: owner,
+                    nonce: MESSAGE_NONCE,
+                    amount: TRANSFER_AMOUNT,
+                    data: vec![],
+                    da_height: DaBlockHeight(0),
+                });
+                Ok(node_config.with_state_config(state_config))
+            }
+            Workload::MultiAssetTransfer => {
+                let mut state_config = node_config.state_config()?;
+                for (index, asset_id) in MULTI_ASSET_IDS.into_iter().enumerate() {
+                    state_config.coins.push(CoinConfig {
+                        tx_id: TxId::new([0xff; 32]),
+                        output_index: u16::try_from(index)?,
+                        owner,
+                        amount: TRANSFER_AMOUNT,
+                        asset_id,
+                        ..Default::default()
+                    });
+                }
+                Ok(node_config.with_state_config(state_config))
+            }
+            Workload::Upload | Workload::Upgrade => {
+                let mut chain_config = node_config.chain_config()?;
+                chain_config
+                    .consensus_parameters
+                    .set_privileged_address(owner);
+                Ok(node_config.with_chain_config(chain_config))
+            }
+            Workload::Script
+            | Workload::ContractDeployment
+            | Workload::ContractCall
+            | Workload::PredicateSpend
+            | Workload::Blob
+            | Workload::Withdrawal => Ok(node_config),
+        }
+    }
+}
+
+impl fmt::Display for Workload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Workload::Script => "script",
+            Workload::ContractDeployment => "contract-deployment",
+            Workload::ContractCall => "contract-call",
+            Workload::PredicateSpend => "predicate-spend",
+            Workload::MessageSpend => "message-spend",
+            Workload::MultiAssetTransfer => "multi-asset-transfer",
+            Workload::Upload => "upload",
+            Workload::Upgrade => "upgrade",
+            Workload::Blob => "blob",
+            Workload::Withdrawal => "withdrawal",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for Workload {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Workload::ALL
+            .into_iter()
+            .find(|workload| workload.to_string() == s)
+            .ok_or_else(|| anyhow::anyhow!("Unknown workload `{s}`"))
+    }
+}
+
+/// The default time to wait for the inclusion of the transaction into the block.
+pub const DEFAULT_INCLUSION_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The error returned when the transaction is not included into the block.
+#[derive(Debug)]
+pub enum InclusionError {
+    /// The node rejected the transaction or the status subscription failed.
+    Client(std::io::Error),
+    /// The transaction was removed from the transaction pool.
+    SqueezedOut { tx_id: TxId, reason: String },
+    /// The transaction was not included within the timeout.
+    Timeout { tx_id: TxId, timeout: Duration },
+    /// The status stream ended before the transaction was included.
+    StreamClosed { tx_id: TxId },
+    /// The manual production of the block with the transaction failed.
+    BlockProduction(anyhow::Error),
+}
+
+impl fmt::Display for InclusionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InclusionError::Client(e) => write!(f, "Node client error: {e}"),
+            InclusionError::SqueezedOut { tx_id, reason } => {
+                write!(f, "Transaction {tx_id} is squeezed out: {reason}")
+            }
+            InclusionError::Timeout { tx_id, timeout } => {
+                write!(f, "Transaction {tx_id} is not included within {timeout:?}")
+            }
+            InclusionError::StreamClosed { tx_id } => {
+                write!(f, "Status stream of the transaction {tx_id} is closed")
+            }
+            InclusionError::BlockProduction(e) => {
+                write!(f, "Unable to produce the block: {e}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for InclusionError {}
+
+impl From<std::io::Error> for InclusionError {
+    fn from(e: std::io::Error) -> Self {
+        InclusionError::Client(e)
+    }
+}
+
+/// Sends transactions of workloads to the node.
+pub struct TransactionSender {
+    client: FuelClient,
+    wallet: WalletUnlocked,
+    secret: SecretKey,
+    consensus_parameters: ConsensusParameters,
+    manual_block_production: Option<ManualBlockProduction>,
+    inclusion_timeout: Duration,
+}
+
+/// Produces a block for each transaction with the deterministic timestamp,
+/// instead of relying on the block production trigger of the node.
+#[derive(Clone)]
+pub struct ManualBlockProduction {
+    poa: PoAAdapter,
+    database: CombinedDatabase,
+}
+
+impl ManualBlockProduction {
+    pub fn new(poa: PoAAdapter, database: CombinedDatabase) -> Self {
+        Self { poa, database }
+    }
+
+    async fn produce_block(&self, tx: Transaction) -> anyhow::Result<BlockHeight> {
+        let block_height = self
+            .database
+            .on_chain()
+            .latest_height()
+            .and_then(|height| height.succ())
+            .ok_or_else(|| anyhow::anyhow!("Unable to get the next block height"))?;
+        let block_time = Tai64(DETERMINISTIC_GENESIS_TIME.0 + u64::from(*block_height));
+
+        self.poa
+            .manually_produce_blocks(
+                Some(block_time),
+                Mode::BlockWithTransactions(vec![tx]),
+            )
+            .await?;
+
+        Ok(block_height)
+    }
+}
+
+impl TransactionSender {
+    pub fn new(
+        client: FuelClient,
+        wallet: WalletUnlocked,
+        secret: SecretKey,
+        consensus_parameters: ConsensusParameters,
+    ) -> Self {
+        Self {
+            client,
+            wallet,
+            secret,
+            consensus_parameters,
+            manual_block_production: None,
+            inclusion_timeout: DEFAULT_INCLUSION_TIMEOUT,
+        }
+    }
+
+    /// Sets the time to wait for the inclusion of each transaction.
+    pub fn with_inclusion_timeout(mut self, inclusion_timeout: Duration) -> Self {
+        self.inclusion_timeout = inclusion_timeout;
+        self
+    }
+
+    /// Includes each transaction into its own manually produced block.
+    pub fn with_manual_block_production(
+        mut self,
+        manual_block_production: ManualBlockProduction,
+    ) -> Self {
+        self.manual_block_production = Some(manual_block_production);
+        self
+    }
+
+    fn owner(&self) -> Address {
+        Input::owner(&self.secret.public_key())
+    }
+
+    fn base_asset_id(&self) -> AssetId {
+        *self.consensus_parameters.base_asset_id()
+    }
+
+    /// Sends transactions of the `workload` and returns the height of the block
+    /// that includes the last of them.
+    pub async fn send(&self, workload: Workload) -> anyhow::Result<BlockHeight> {
+        match workload {
+            Workload::Script => self.send_script_transaction().await,
+            Workload::ContractDeployment => {
+                let (_, block_height) = self.deploy_contract().await?;
+                Ok(block_height)
+            }
+            Workload::ContractCall => self.call_contract().await,
+            Workload::PredicateSpend => self.spend_predicate().await,
+            Workload::MessageSpend => self.spend_message().await,
+            Workload::MultiAssetTransfer => self.transfer_multiple_assets().await,
+            Workload::Upload => self.upload().await,
+            Workload::Upgrade => self.upgrade().await,
+            Workload::Blob => self.upload_blob().await,
+            Workload::Withdrawal => self.withdraw().await,
+        }
+    }
+
+    async fn send_script_transaction(&self) -> anyhow::Result<BlockHeight> {
+        let script = [
+            op::movi(0x10, 1024),
+            op::addi(0x11, 0x10, 1024),
+            op::jmpb(RegId::ZERO, 0),
+        ]
+        .into_iter()
+        .collect();
+
+        let mut builder = ScriptTransactionBuilder::default().with_script(script);
+        self.wallet.add_witnesses(&mut builder)?;
+        self.wallet.adjust_for_fee(&mut builder, 0).await?;
+        let provider = self.wallet.provider().expect("No provider");
+        let tx = builder.build(provider).await?;
+
+        Ok(self.submit(tx.into()).await?)
+    }
+
+    async fn deploy_contract(&self) -> anyhow::Result<(ContractId, BlockHeight)> {
+        // The contract returns `1` for any call.
+        let bytecode: Vec<u8> = [op::ret(RegId::ONE)].into_iter().collect();
+        let salt = Salt::zeroed();
+        let contract = Contract::from(bytecode.clone());
+        let root = contract.root();
+        let state_root = Contract::default_state_root();
+        let contract_id = contract.id(&salt, &root, &state_root);
+
+        let mut builder = TransactionBuilder::create(bytecode.into(), salt, vec![]);
+        builder.add_output(Output::contract_created(contract_id, state_root));
+        self.pay_fee(&mut builder).await?;
+
+        let block_height = self.submit(builder.finalize_as_transaction()).await?;
+        Ok((contract_id, block_height))
+    }
+
+    async fn call_contract(&self) -> anyhow::Result<BlockHeight> {
+        let (contract_id, _) = self.deploy_contract().await?;
+
+        let script = [
+            op::gtf_args(0x10, RegId::ZERO, GTFArgs::ScriptData),
+            op::call(0x10, RegId::ZERO, RegId::ZERO, RegId::CGAS),
+            op::ret(RegId::ONE),
+        ]
+        .into_iter()
+        .collect();
+        // The call frame: contract id and two zero parameters.
+        let script_data = contract_id
+            .iter()
+            .copied()
+            .chain(0u64.to_be_bytes())
+            .chain(0u64.to_be_bytes())
+            .collect();
+
+        let mut builder = TransactionBuilder::script(script, script_data);
+        builder
+            .script_gas_limit(SCRIPT_GAS_LIMIT)
+            .add_input(Input::contract(
+                UtxoId::default(),
+                Bytes32::zeroed(),
+                Bytes32::zeroed(),
+                TxPointer::default(),
+                contract_id,
+            ))
+            .add_output(Output::contract(0, Bytes32::zeroed(), Bytes32::zeroed()));
+        self.pay_fee(&mut builder).await?;
+
+        Ok(self.submit(builder.finalize_as_transaction()).await?)
+    }
+
+    async fn spend_predicate(&self) -> anyhow::Result<BlockHeight> {
+        // The predicate that is always true.
+        let predicate: Vec<u8> = [op::ret(RegId::ONE)].into_iter().collect();
+        let predicate_owner = Input::predicate_owner(&predicate);
+        let base_asset_id = self.base_asset_id();
+
+        let tx = self
+            .transfer_transaction(predicate_owner, TRANSFER_AMOUNT)
+            .await?;
+        self.submit(tx).await?;
+
+        let coin = self.largest_coin(&predicate_owner, base_asset_id).await?;
+        let mut builder = TransactionBuilder::script(vec![], vec![]);
+        builder
+            .script_gas_limit(SCRIPT_GAS_LIMIT)
+            .max_fee_limit(MAX_FEE)
+            .with_params(self.consensus_parameters.clone())
+            .add_input(Input::coin_predicate(
+                coin.utxo_id,
+                predicate_owner,
+                coin.amount,
+                coin.asset_id,
+                TxPointer::new(coin.block_created.into(), coin.tx_created_idx),
+                0,
+                predicate,
+                vec![],
+            ))
+            .add_output(Output::change(self.owner(), 0, base_asset_id));
+
+        let mut tx = builder.finalize_as_transaction();
+        self.client.estimate_predicates(&mut tx).await?;
+
+        Ok(self.submit(tx).await?)
+    }
+
+    async fn spend_message(&self) -> anyhow::Result<BlockHeight> {
+        let tx = self.message_spend_transaction(
+            Address::zeroed(),
+            MESSAGE_NONCE,
+            TRANSFER_AMOUNT,
+        );
+
+        Ok(self.submit(tx).await?)
+    }
+
+    /// Builds the transaction that spends the message coin sent to the wallet.
+    pub fn message_spend_transaction(
+        &self,
+        sender: Address,
+        nonce: Nonce,
+        amount: Word,
+    ) -> Transaction {
+        let mut builder = TransactionBuilder::script(vec![], vec![]);
+        builder
+            .script_gas_limit(SCRIPT_GAS_LIMIT)
+            .max_fee_limit(MAX_FEE)
+            .with_params(self.consensus_parameters.clone())
+            .add_unsigned_message_input(self.secret, sender, nonce, amount, vec![])
+            .add_output(Output::change(self.owner(), 0, self.base_asset_id()));
+
+        builder.finalize_as_transaction()
+    }
+
+    /// Builds the transaction that transfers the `amount` of the base asset
+    /// from the wallet to the `recipient`.
+    pub async fn transfer_transaction(
+        &self,
+        recipient: Address,
+        amount: Word,
+    ) -> anyhow::Result<Transaction> {
+        let mut builder = TransactionBuilder::script(vec![], vec![]);
+        builder
+            .script_gas_limit(SCRIPT_GAS_LIMIT)
+            .add_output(Output::coin(recipient, amount, self.base_asset_id()));
+        self.pay_fee(&mut builder).await?;
+
+        Ok(builder.finalize_as_transaction())
+    }
+
+    async fn transfer_multiple_assets(&self) -> anyhow::Result<BlockHeight> {
+        let recipient = Address::new([0xaa; 32]);
+
+        let mut builder = TransactionBuilder::script(vec![], vec![]);
+        builder.script_gas_limit(SCRIPT_GAS_LIMIT);
+        for asset_id in MULTI_ASSET_IDS {
+            let coin = self.largest_coin(&self.owner(), asset_id).await?;
+            builder
+                .add_unsigned_coin_input(
+                    self.secret,
+                    coin.utxo_id,
+                    coin.amount,
+                    coin.asset_id,
+                    TxPointer::new(coin.block_created.into(), coin.tx_created_idx),
+                )
+                .add_output(Output::coin(recipient, coin.amount / 2, asset_id))
+                .add_output(Output::change(self.owner(), 0, asset_id));
+        }
+        self.pay_fee(&mut builder).await?;
+
+        Ok(self.submit(builder.finalize_as_transaction()).await?)
+    }
+
+    async fn upload(&self) -> anyhow::Result<BlockHeight> {
+        let bytecode = vec![0xaa; UPLOAD_BYTECODE_SIZE];
+        let subsections =
+            UploadSubsection::split_bytecode(&bytecode, UPLOAD_BYTECODE_SIZE)
+                .map_err(|e| anyhow::anyhow!("Unable to split the bytecode: {e:?}"))?;
+
+        let mut block_height = None;
+        for subsection in subsections {
+            let UploadSubsection {
+                root,
+                subsection,
+                subsection_index,
+                subsections_number,
+                proof_set,
+            } = subsection;
+
+            let mut builder = TransactionBuilder::upload(UploadBody {
+                root,
+                witness_index: 0,
+                subsection_index,
+                subsections_number,
+                proof_set,
+            });
+            builder.add_witness(subsection.into());
+            self.pay_fee(&mut builder).await?;
+
+            block_height = Some(self.submit(builder.finalize_as_transaction()).await?);
+        }
+
+        block_height.ok_or_else(|| anyhow::anyhow!("The bytecode has no subsections"))
+    }
+
+    async fn upgrade(&self) -> anyhow::Result<BlockHeight> {
+        // The new version of the consensus parameters is the same as the current one.
+        // It is enough to exercise the upgrade.
+        let consensus_parameters = postcard::to_allocvec(&self.consensus_parameters)?;
+        let checksum = Hasher::hash(&consensus_parameters);
+
+        let mut builder =
+            TransactionBuilder::upgrade(UpgradePurpose::ConsensusParameters {
+                witness_index: 0,
+                checksum,
+            });
+        builder.add_witness(consensus_parameters.into());
+        self.pay_fee(&mut builder).await?;
+
+        Ok(self.submit(builder.finalize_as_transaction()).await?)
+    }
+
+    async fn upload_blob(&self) -> anyhow::Result<BlockHeight> {
+        let blob: Vec<u8> = [op::ret(RegId::ONE)].into_iter().collect();
+
+        let mut builder = TransactionBuilder::blob(BlobBody {
+            id: BlobId::compute(&blob),
+            witness_index: 0,
+        });
+        builder.add_witness(blob.into());
+        self.pay_fee(&mut builder).await?;
+
+        Ok(self.submit(builder.finalize_as_transaction()).await?)
+    }
+
+    async fn withdraw(&self) -> anyhow::Result<BlockHeight> {
+        let script = [
+            op::gtf_args(0x10, RegId::ZERO, GTFArgs::ScriptData),
+            op::movi(0x11, WITHDRAWAL_AMOUNT),
+            op::smo(0x10, RegId::ZERO, RegId::ZERO, 0x11),
+            op::ret(RegId::ONE),
+        ]
+        .into_iter()
+        .collect();
+        let script_data = WITHDRAWAL_RECIPIENT.to_vec();
+
+        let mut builder = TransactionBuilder::script(script, script_data);
+        builder.script_gas_limit(SCRIPT_GAS_LIMIT);
+        self.pay_fee(&mut builder).await?;
+
+        Ok(self.submit(builder.finalize_as_transaction()).await?)
+    }
+
+    /// Adds the base asset coin of the wallet to pay for the transaction
+    /// and the change output for it.
+    async fn pay_fee<Tx>(
+        &self,
+        builder: &mut TransactionBuilder<Tx>,
+    ) -> anyhow::Result<()>
+    where
+        Tx: Buildable,
+    {
+        let base_asset_id = self.base_asset_id();
+        let coin = self.largest_coin(&self.owner(), base_asset_id).await?;
+
+        builder
+            .max_fee_limit(MAX_FEE)
+            .with_params(self.consensus_parameters.clone())
+            .add_unsigned_coin_input(
+                self.secret,
+                coin.utxo_id,
+                coin.amount,
+                coin.asset_id,
+                TxPointer::new(coin.block_created.into(), coin.tx_created_idx),
+            )
+            .add_output(Output::change(self.owner(), 0, base_asset_id));
+
+        Ok(())
+    }
+
+    async fn largest_coin(
+        &self,
+        owner: &Address,
+        asset_id: AssetId,
+    ) -> anyhow::Result<Coin> {
+        let request = PaginationRequest {
+            cursor: None,
+            results: 100,
+            direction: PageDirection::Forward,
+        };
+        self.client
+            .coins(owner, Some(&asset_id), request)
+            .await?
+            .results
+            .into_iter()
+            .max_by_key(|coin| coin.amount)
+            .ok_or_else(|| anyhow::anyhow!("No coins of {asset_id} owned by {owner}"))
+    }
+
+    /// Submits the transaction and waits for its inclusion into the block.
+    /// Returns the height of the block that includes the transaction.
+    pub async fn submit(&self, tx: Transaction) -> Result<BlockHeight, InclusionError> {
+        if let Some(manual_block_production) = &self.manual_block_production {
+            return manual_block_production
+                .produce_block(tx)
+                .await
+                .map_err(InclusionError::BlockProduction);
+        }
+
+        let tx_id = tx.id(&self.consensus_parameters.chain_id());
+        let inclusion = async {
+            let mut statuses = Box::pin(self.client.submit_and_await_status(&tx).await?);
+
+            while let Some(status) = statuses.next().await {
+                match status? {
+                    TransactionStatus::Submitted { .. } => continue,
+                    TransactionStatus::Success { block_height, .. } => {
+                        return Ok(block_height);
+                    }
+                    // The failed transaction is still a part of the block.
+                    TransactionStatus::Failure {
+                        block_height,
+                        reason,
+                        ..
+                    } => {
+                        tracing::warn!("Transaction {tx_id} failed: {reason}");
+                        return Ok(block_height);
+                    }
+                    TransactionStatus::SqueezedOut { reason } => {
+                        return Err(InclusionError::SqueezedOut { tx_id, reason });
+                    }
+                }
+            }
+
+            Err(InclusionError::StreamClosed { tx_id })
+        };
+
+        tokio::time::timeout(self.inclusion_timeout, inclusion)
+            .await
+            .map_err(|_| InclusionError::Timeout {
+                tx_id,
+                timeout: self.inclusion_timeout,
+            })?
+    }
+}
@@ -8,23 +8,37 @@
 #![no_main]
 sp1_zkvm::entrypoint!(main);
 
-use alloy_sol_types::SolType;
 use prover::{
-    prove,
-    PublicValuesStruct,
+    CycleTracker,
+    Mode,
+    Phase,
 };
 
+/// Reports the phases to the `cycle_tracker` of the execution report of SP1.
+struct ReportCycleTracker;
+
+impl CycleTracker for ReportCycleTracker {
+    fn start(&self, phase: Phase) {
+        println!("cycle-tracker-report-start: {}", phase.name());
+    }
+
+    fn end(&self, phase: Phase) {
+        println!("cycle-tracker-report-end: {}", phase.name());
+    }
+}
+
 pub fn main() {
     // Read an input to the program.
     //
     // Behind the scenes, this compiles down to a custom system call which handles reading inputs
     // from the prover.
+    let mode = sp1_zkvm::io::read::<Mode>();
     let bytes = sp1_zkvm::io::read_vec();
 
-    let proof = prove(&bytes).expect("Proof generation failed");
-
-    // Encode the public values of the program.
-    let bytes = PublicValuesStruct::abi_encode(&proof);
+    // Prove the input and encode the public values of the program.
+    let bytes = mode
+        .prove_tracked(&bytes, &ReportCycleTracker)
+        .expect("Proof generation failed");
 
     // Commit to the public values of the program. The final proof will have a commitment to all the
     // bytes that were committed to.
@@ -6,32 +6,76 @@
 use crate::memory::PanicStorage;
 use alloc::collections::BTreeMap;
 use alloy_sol_types::{
-    private::U256,
+    private::{
+        FixedBytes,
+        U256,
+    },
     sol,
+    SolStruct,
+    SolType,
 };
 use core::cell::RefCell;
 use fuel_core_executor::{
     executor::{
         ExecutionInstance,
         ExecutionOptions,
+        OnceTransactionsSource,
     },
     ports::RelayerPort,
 };
-use fuel_core_storage::transactional::{
-    Changes,
-    ConflictPolicy,
-    StorageTransaction,
+use fuel_core_storage::{
+    tables::{
+        ConsensusParametersVersions,
+        FuelBlocks,
+    },
+    transactional::{
+        Changes,
+        ConflictPolicy,
+        StorageTransaction,
+    },
+    StorageAsRef,
 };
 use fuel_core_types::{
     blockchain::{
         block::Block,
-        primitives::DaBlockHeight,
+        header::{
+            ApplicationHeader,
+            BlockHeader,
+            ConsensusHeader,
+            PartialBlockHeader,
+        },
+        primitives::{
+            DaBlockHeight,
+            Empty,
+        },
     },
     fuel_crypto,
+    fuel_merkle::binary::{
+        self,
+        in_memory::MerkleTree,
+        root_calculator::MerkleRootCalculator,
+    },
+    fuel_tx::{
+        Bytes32,
+        ConsensusParameters,
+        ContractId,
+        MessageId,
+        Receipt,
+        Transaction,
+        UniqueIdentifier,
+    },
+    fuel_types::{
+        canonical::Serialize,
+        BlockHeight,
+    },
     services::{
+        block_producer::Components,
         executor::{
             Error as ExecutorError,
+            Event as ExecutorEvent,
             Result as ExecutorResult,
+            TransactionExecutionResult,
+            TransactionExecutionStatus,
         },
         relayer::Event,
     },
@@ -39,44 +83,258 @@ use fuel_core_types::{
 
 extern crate alloc;
 
+/// The public values structs, shared by the `sol!` declarations and [`solidity_library`],
+/// so the contracts decode exactly what the guest programs commit.
+const PUBLIC_VALUES_SOL: &str = include_str!("public_values.sol");
+
 sol! {
-    /// The public values encoded as a struct that can be easily deserialized inside Solidity.
-    struct PublicValuesStruct {
-        uint256 input_hash;
-        uint256 block_id;
-    }
+    #[sol(all_derives)]
+    "src/public_values.sol"
 }
 
+/// The `mode` committed in the [`PublicValuesStruct`] by the validation of the block.
+pub const MODE_VALIDATION: u8 = 0;
+/// The `mode` committed in the [`PublicValuesStruct`] by the production of the block.
+pub const MODE_PRODUCTION: u8 = 1;
+/// The `mode` committed in the [`TransactionPublicValuesStruct`].
+pub const MODE_TRANSACTION: u8 = 2;
+
+/// The `status` committed in the [`TransactionPublicValuesStruct`]
+/// when the transaction is executed successfully.
+pub const TRANSACTION_STATUS_SUCCESS: u8 = 0;
+/// The `status` committed in the [`TransactionPublicValuesStruct`]
+/// when the transaction is reverted or panicked.
+pub const TRANSACTION_STATUS_FAILURE: u8 = 1;
+
+/// The header of the Solidity library generated by [`solidity_library`].
+const SOLIDITY_HEADER: &str = "// SPDX-License-Identifier: MIT
+// Generated from `prover/src/public_values.sol` by `cargo run -p prover --bin gen-solidity`,
+// do not edit.
+pragma solidity ^0.8.20;
+";
+
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct Input {
     pub block: Block,
+    #[serde(with = "ordered_changes")]
+    pub storage: Changes,
+    pub relayer: Relayer,
+}
+
+/// The input to prove the production of the block from the header template
+/// and the list of transactions, for example, the snapshot of the mempool.
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct ProductionInput {
+    pub application: ApplicationHeader<Empty>,
+    pub consensus: ConsensusHeader<Empty>,
+    pub transactions: Vec<Transaction>,
+    pub coinbase_recipient: ContractId,
+    pub gas_price: u64,
+    #[serde(with = "ordered_changes")]
+    pub storage: Changes,
+    pub relayer: Relayer,
+}
+
+/// The input to prove the execution of one transaction in the context of the block.
+/// The block context is the same as for the [`ProductionInput`].
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct TransactionInput {
+    pub application: ApplicationHeader<Empty>,
+    pub consensus: ConsensusHeader<Empty>,
+    pub transaction: Transaction,
+    pub coinbase_recipient: ContractId,
+    pub gas_price: u64,
+    #[serde(with = "ordered_changes")]
     pub storage: Changes,
     pub relayer: Relayer,
 }
 
+/// Selects what the guest program proves.
+#[derive(
+    serde::Serialize, serde::Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq,
+)]
+pub enum Mode {
+    /// The input is the [`Input`], and the already produced block is validated.
+    #[default]
+    Validation,
+    /// The input is the [`ProductionInput`], and the block is produced.
+    Production,
+    /// The input is the [`TransactionInput`], and only the transaction is executed.
+    Transaction,
+}
+
+impl Mode {
+    /// Returns the tag committed as the `mode` of the public values. The guest runs every
+    /// mode with the same image, so the verifiers tell the proofs apart by the tag.
+    pub const fn tag(self) -> u8 {
+        match self {
+            Mode::Validation => MODE_VALIDATION,
+            Mode::Production => MODE_PRODUCTION,
+            Mode::Transaction => MODE_TRANSACTION,
+        }
+    }
+
+    /// Proves the `input_bytes` according to the mode and returns the ABI encoded
+    /// public values: the [`TransactionPublicValuesStruct`] for the [`Mode::Transaction`]
+    /// and the [`PublicValuesStruct`] otherwise.
+    pub fn prove(self, input_bytes: &[u8]) -> ExecutorResult<Vec<u8>> {
+        self.prove_tracked(input_bytes, &())
+    }
+
+    /// Proves the `input_bytes` like [`Mode::prove`], reporting the phases to the `tracker`.
+    pub fn prove_tracked(
+        self,
+        input_bytes: &[u8],
+        tracker: &impl CycleTracker,
+    ) -> ExecutorResult<Vec<u8>> {
+        let bytes = match self {
+            Mode::Validation => {
+                PublicValuesStruct::abi_encode(&prove_tracked(input_bytes, tracker)?)
+            }
+            Mode::Production => PublicValuesStruct::abi_encode(
+                &prove_production_tracked(input_bytes, tracker)?,
+            ),
+            Mode::Transaction => TransactionPublicValuesStruct::abi_encode(
+                &prove_transaction_tracked(input_bytes, tracker)?,
+            ),
+        };
+        Ok(bytes)
+    }
+}
+
+/// The phases of proving, profiled by the guests with the cycle tracking of their zkVM.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Phase {
+    /// Decoding the bincode encoded input.
+    Deserialization,
+    /// Creating the storage from the witness and reading the consensus parameters
+    /// and the parent block.
+    StorageSetup,
+    /// Executing the block, or the transaction in the [`Mode::Transaction`]. The executor runs
+    /// the whole block in one call, so the transactions of the block are not tracked one by one.
+    Execution,
+    /// Computing the roots and the hashes of the public values.
+    PublicValues,
+    /// Hashing the input committed as the `input_hash`.
+    InputHashing,
+}
+
+impl Phase {
+    pub const ALL: [Phase; 5] = [
+        Phase::Deserialization,
+        Phase::StorageSetup,
+        Phase::Execution,
+        Phase::PublicValues,
+        Phase::InputHashing,
+    ];
+
+    /// The name of the phase in the cycle tracking reports.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Phase::Deserialization => "deserialize-input",
+            Phase::StorageSetup => "setup-storage",
+            Phase::Execution => "execute",
+            Phase::PublicValues => "compute-public-values",
+            Phase::InputHashing => "hash-input",
+        }
+    }
+}
+
+/// Tracks the cycles of the [`Phase`]s in the zkVM. The unit tracker ignores them.
+pub trait CycleTracker {
+    fn start(&self, phase: Phase);
+    fn end(&self, phase: Phase);
+}
+
+impl CycleTracker for () {
+    fn start(&self, _: Phase) {}
+    fn end(&self, _: Phase) {}
+}
+
+/// Runs `f` as the `phase` tracked by the `tracker`.
+fn track<T>(tracker: &impl CycleTracker, phase: Phase, f: impl FnOnce() -> T) -> T {
+    tracker.start(phase);
+    let result = f();
+    tracker.end(phase);
+    result
+}
+
 #[derive(Default, Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct Relayer(RefCell<BTreeMap<DaBlockHeight, Vec<Event>>>);
+pub struct Relayer {
+    events: RefCell<BTreeMap<DaBlockHeight, Vec<Event>>>,
+    /// The events requested by the executor, in the order of processing.
+    #[serde(skip)]
+    processed_events: RefCell<Vec<Event>>,
+}
 
 impl Relayer {
     pub fn new() -> Self {
-        Self(RefCell::new(BTreeMap::new()))
+        Self::default()
     }
 
     pub fn add_event(&self, da_block_height: DaBlockHeight, events: Vec<Event>) {
-        self.0.borrow_mut().insert(da_block_height, events);
+        self.events.borrow_mut().insert(da_block_height, events);
+    }
+
+    /// Returns ids of the relayed transactions processed by the executor.
+    /// It includes transactions that were skipped because of the failure.
+    pub fn processed_relayed_transactions(&self) -> Vec<Bytes32> {
+        self.processed_events
+            .borrow()
+            .iter()
+            .filter_map(|event| match event {
+                Event::Transaction(transaction) => Some(transaction.id().into()),
+                Event::Message(_) => None,
+            })
+            .collect()
     }
 }
 
-impl RelayerPort for Relayer {
+impl RelayerPort for &Relayer {
     fn enabled(&self) -> bool {
         true
     }
 
     fn get_events(&self, da_block_height: &DaBlockHeight) -> anyhow::Result<Vec<Event>> {
-        self.0
+        let events = self
+            .events
             .borrow_mut()
             .remove(da_block_height)
-            .ok_or_else(|| anyhow::anyhow!("Not found"))
+            .ok_or_else(|| anyhow::anyhow!("Not found"))?;
+        self.processed_events
+            .borrow_mut()
+            .extend(events.iter().cloned());
+        Ok(events)
+    }
+}
+
+/// Serializes the storage changes ordered by the column.
+/// `Changes` is a hash map, so the default serialization order is random,
+/// while the input should be deterministic to have the same `input_hash`.
+mod ordered_changes {
+    use alloc::collections::BTreeMap;
+    use fuel_core_storage::transactional::Changes;
+    use serde::{
+        Deserialize,
+        Deserializer,
+        Serialize,
+        Serializer,
+    };
+
+    pub fn serialize<S>(changes: &Changes, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let ordered: BTreeMap<_, _> = changes.iter().collect();
+        ordered.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Changes, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let ordered: BTreeMap<u32, _> = BTreeMap::deserialize(deserializer)?;
+        Ok(ordered.into_iter().collect())
     }
 }
 
@@ -107,9 +365,214 @@ mod memory {
     }
 }
 
+/// Returns the hash of the serialized input committed as the `input_hash`.
+/// The verifier can use it to check which input the proof was generated for.
+pub fn input_hash(input_bytes: &[u8]) -> Bytes32 {
+    fuel_crypto::Hasher::hash(input_bytes)
+}
+
+/// Returns the hash of the consensus parameters committed by the [`prove`] function.
+/// The verifier can use it to check which gas costs and limits were used to validate the block.
+///
+/// The hash is the SHA-256 of the postcard encoding of the consensus parameters, the encoding
+/// used by the upgrade transactions on-chain. So it is the same as the `checksum` of the upgrade
+/// transaction that set the parameters, and the verifier can compare it with the L1 records.
+pub fn consensus_parameters_hash(consensus_parameters: &ConsensusParameters) -> Bytes32 {
+    let bytes = postcard::to_allocvec(consensus_parameters)
+        .expect("Consensus parameters are serializable");
+    fuel_crypto::Hasher::hash(bytes)
+}
+
+/// Returns the root of the binary Merkle tree of the canonically serialized `receipts`,
+/// the same as the `receipts_root` of the script transaction.
+pub fn receipts_root(receipts: &[Receipt]) -> Bytes32 {
+    let mut root = MerkleRootCalculator::new();
+    for receipt in receipts {
+        root.push(receipt.to_bytes().as_slice());
+    }
+    root.root().into()
+}
+
+fn consensus_parameters(
+    storage: &StorageTransaction<PanicStorage>,
+    consensus_parameters_version: u32,
+) -> ExecutorResult<ConsensusParameters> {
+    let consensus_parameters = storage
+        .storage_as_ref::<ConsensusParametersVersions>()
+        .get(&consensus_parameters_version)
+        .map_err(|e| ExecutorError::Other(format!("Unable to read the consensus parameters {e}")))?
+        .ok_or_else(|| {
+            ExecutorError::Other(format!(
+                "The consensus parameters of version {consensus_parameters_version} are not found"
+            ))
+        })?
+        .into_owned();
+    Ok(consensus_parameters)
+}
+
+/// Returns the id of the parent block. Its header is a part of the storage witness,
+/// so proofs of consecutive blocks can be checked to form a chain.
+fn parent_block_id(
+    storage: &StorageTransaction<PanicStorage>,
+    block_height: BlockHeight,
+) -> ExecutorResult<Bytes32> {
+    let parent_height = block_height
+        .pred()
+        .ok_or_else(|| ExecutorError::Other("The genesis block has no parent".into()))?;
+    let parent_block = storage
+        .storage_as_ref::<FuelBlocks>()
+        .get(&parent_height)
+        .map_err(|e| {
+            ExecutorError::Other(format!("Unable to read the parent block {e}"))
+        })?
+        .ok_or_else(|| {
+            ExecutorError::Other(format!(
+                "The parent block at {parent_height} is not found"
+            ))
+        })?;
+    Ok(parent_block.header().id().into())
+}
+
+fn execution_options() -> ExecutionOptions {
+    ExecutionOptions {
+        extra_tx_checks: true,
+        backtrace: false,
+    }
+}
+
+/// Returns the root of the binary Merkle tree of `message_ids`,
+/// the same as the `message_outbox_root` of the block header.
+pub fn message_outbox_root(message_ids: &[MessageId]) -> Bytes32 {
+    let mut root = MerkleRootCalculator::new();
+    for message_id in message_ids {
+        root.push(message_id.as_ref());
+    }
+    root.root().into()
+}
+
+/// The proof that the message was emitted in the block with the `message_outbox_root`.
+/// The withdrawal contract on L1 verifies it against the root committed by the prover.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageProof {
+    pub message_id: MessageId,
+    pub message_outbox_root: Bytes32,
+    pub proof_index: u64,
+    pub num_leaves: u64,
+    pub proof_set: Vec<Bytes32>,
+}
+
+impl MessageProof {
+    pub fn verify(&self) -> bool {
+        let proof_set = self.proof_set.iter().map(|node| **node).collect::<Vec<_>>();
+        binary::verify(
+            &self.message_outbox_root,
+            &self.message_id,
+            &proof_set,
+            self.proof_index,
+            self.num_leaves,
+        )
+    }
+}
+
+/// Builds the Merkle proof of the `message_id` among `message_ids` of the block.
+/// Returns `None` if the block doesn't emit the message.
+pub fn message_proof(
+    message_ids: &[MessageId],
+    message_id: &MessageId,
+) -> Option<MessageProof> {
+    let proof_index = message_ids.iter().position(|id| id == message_id)?;
+    let mut tree = MerkleTree::new();
+    for message_id in message_ids {
+        tree.push(message_id.as_ref());
+    }
+    let (message_outbox_root, proof_set) = tree.prove(proof_index as u64)?;
+
+    Some(MessageProof {
+        message_id: *message_id,
+        message_outbox_root: message_outbox_root.into(),
+        proof_index: proof_index as u64,
+        num_leaves: message_ids.len() as u64,
+        proof_set: proof_set.into_iter().map(Into::into).collect(),
+    })
+}
+
+/// Returns ids of the messages emitted by `MessageOut` receipts in the order of execution.
+pub fn message_ids(tx_status: &[TransactionExecutionStatus]) -> Vec<MessageId> {
+    tx_status
+        .iter()
+        .flat_map(|status| match &status.result {
+            TransactionExecutionResult::Success { receipts, .. }
+            | TransactionExecutionResult::Failed { receipts, .. } => receipts.iter(),
+        })
+        .filter_map(Receipt::message_id)
+        .collect()
+}
+
+/// Returns the public values of the block committed by the [`Mode::Validation`].
+/// The production overrides the `mode` of the returned values.
+fn public_values(
+    input_hash: Bytes32,
+    header: &BlockHeader,
+    parent_block_id: Bytes32,
+    consensus_parameters: &ConsensusParameters,
+    relayer: &Relayer,
+    events: &[ExecutorEvent],
+    tx_status: &[TransactionExecutionStatus],
+) -> PublicValuesStruct {
+    // Relayed transactions that fail are not included into the block,
+    // but they are still processed and recorded as skipped.
+    let failed_relayed_transactions = events
+        .iter()
+        .filter_map(|event| match event {
+            ExecutorEvent::ForcedTransactionFailed { id, .. } => {
+                Some(FixedBytes::from(*Bytes32::from(id.clone())))
+            }
+            _ => None,
+        })
+        .collect();
+    let processed_relayed_transactions = relayer
+        .processed_relayed_transactions()
+        .into_iter()
+        .map(|id| FixedBytes::from(*id))
+        .collect();
+
+    // The bridge proves withdrawals against the root of the messages emitted in the block.
+    let message_ids = message_ids(tx_status);
+    let message_outbox_root = message_outbox_root(&message_ids);
+
+    let consensus_parameters_hash = consensus_parameters_hash(consensus_parameters);
+
+    PublicValuesStruct {
+        mode: Mode::Validation.tag(),
+        input_hash: U256::from_be_bytes(input_hash.into()),
+        block_id: U256::from_be_bytes(header.id().into()),
+        parent_block_id: U256::from_be_bytes(parent_block_id.into()),
+        block_height: **header.height(),
+        consensus_parameters_version: header.application().consensus_parameters_version,
+        consensus_parameters_hash: U256::from_be_bytes(consensus_parameters_hash.into()),
+        processed_relayed_transactions,
+        failed_relayed_transactions,
+        message_outbox_root: FixedBytes::from(*message_outbox_root),
+        message_ids: message_ids
+            .into_iter()
+            .map(|id| FixedBytes::from(*id))
+            .collect(),
+    }
+}
+
 pub fn prove(input_bytes: &[u8]) -> ExecutorResult<PublicValuesStruct> {
-    let input: Input = bincode::deserialize_from(input_bytes)
-        .map_err(|e| ExecutorError::Other(format!("Unable to decode the input {e}")))?;
+    prove_tracked(input_bytes, &())
+}
+
+/// Validates the block like [`prove`], reporting the phases to the `tracker`.
+pub fn prove_tracked(
+    input_bytes: &[u8],
+    tracker: &impl CycleTracker,
+) -> ExecutorResult<PublicValuesStruct> {
+    let input: Input = track(tracker, Phase::Deserialization, || {
+        bincode::deserialize_from(input_bytes)
+            .map_err(|e| ExecutorError::Other(format!("Unable to decode the input {e}")))
+    })?;
 
     let Input {
         block,
@@ -117,29 +580,319 @@ pub fn prove(input_bytes: &[u8]) -> ExecutorResult<PublicValuesStruct> {
         relayer,
     } = input;
 
-    let panic_storage = PanicStorage::default();
-    let storage =
-        StorageTransaction::transaction(panic_storage, ConflictPolicy::Fail, storage);
+    let (validator, consensus_parameters, parent_block_id) =
+        track(tracker, Phase::StorageSetup, || {
+            let panic_storage = PanicStorage;
+            let storage = StorageTransaction::transaction(
+                panic_storage,
+                ConflictPolicy::Fail,
+                storage,
+            );
+
+            // The executor uses the consensus parameters of the version from the block header,
+            // so they are always a part of the storage witness.
+            let consensus_parameters_version =
+                block.header().application().consensus_parameters_version;
+            let consensus_parameters =
+                consensus_parameters(&storage, consensus_parameters_version)?;
+            let parent_block_id = parent_block_id(&storage, *block.header().height())?;
+
+            let validator =
+                ExecutionInstance::new(&relayer, storage, execution_options());
+            ExecutorResult::Ok((validator, consensus_parameters, parent_block_id))
+        })?;
+
+    let result = track(tracker, Phase::Execution, || {
+        validator.validate_without_commit(&block)
+    })?
+    .into_result();
+
+    let input_hash = track(tracker, Phase::InputHashing, || input_hash(input_bytes));
+    Ok(track(tracker, Phase::PublicValues, || {
+        public_values(
+            input_hash,
+            block.header(),
+            parent_block_id,
+            &consensus_parameters,
+            &relayer,
+            &result.events,
+            &result.tx_status,
+        )
+    }))
+}
+
+/// Produces the block from the [`ProductionInput`] and commits the id of the produced block.
+/// Transactions that are invalid are skipped by the executor and are not included into the block.
+pub fn prove_production(input_bytes: &[u8]) -> ExecutorResult<PublicValuesStruct> {
+    prove_production_tracked(input_bytes, &())
+}
+
+/// Produces the block like [`prove_production`], reporting the phases to the `tracker`.
+pub fn prove_production_tracked(
+    input_bytes: &[u8],
+    tracker: &impl CycleTracker,
+) -> ExecutorResult<PublicValuesStruct> {
+    let input: ProductionInput = track(tracker, Phase::Deserialization, || {
+        bincode::deserialize_from(input_bytes)
+            .map_err(|e| ExecutorError::Other(format!("Unable to decode the input {e}")))
+    })?;
 
-    let validator = ExecutionInstance::new(
+    let ProductionInput {
+        application,
+        consensus,
+        transactions,
+        coinbase_recipient,
+        gas_price,
+        storage,
         relayer,
+    } = input;
+    let header_to_produce = PartialBlockHeader {
+        application,
+        consensus,
+    };
+
+    let (producer, consensus_parameters, parent_block_id) =
+        track(tracker, Phase::StorageSetup, || {
+            let panic_storage = PanicStorage;
+            let storage = StorageTransaction::transaction(
+                panic_storage,
+                ConflictPolicy::Fail,
+                storage,
+            );
+
+            let consensus_parameters_version =
+                header_to_produce.application.consensus_parameters_version;
+            let consensus_parameters =
+                consensus_parameters(&storage, consensus_parameters_version)?;
+            let parent_block_id =
+                parent_block_id(&storage, header_to_produce.consensus.height)?;
+
+            let producer = ExecutionInstance::new(&relayer, storage, execution_options());
+            ExecutorResult::Ok((producer, consensus_parameters, parent_block_id))
+        })?;
+
+    let components = Components {
+        header_to_produce,
+        transactions_source: OnceTransactionsSource::new(transactions),
+        coinbase_recipient,
+        gas_price,
+    };
+    // The header of the produced block is computed by the executor.
+    let result = track(tracker, Phase::Execution, || {
+        producer.produce_without_commit(components, false)
+    })?
+    .into_result();
+
+    let input_hash = track(tracker, Phase::InputHashing, || input_hash(input_bytes));
+    Ok(track(tracker, Phase::PublicValues, || PublicValuesStruct {
+        mode: Mode::Production.tag(),
+        ..public_values(
+            input_hash,
+            result.block.header(),
+            parent_block_id,
+            &consensus_parameters,
+            &relayer,
+            &result.events,
+            &result.tx_status,
+        )
+    }))
+}
+
+/// Executes the transaction from the [`TransactionInput`] in the block context
+/// and commits its id, the root of its receipts and the status of the execution.
+/// The proof fails if the transaction is invalid and can't be included into the block.
+pub fn prove_transaction(
+    input_bytes: &[u8],
+) -> ExecutorResult<TransactionPublicValuesStruct> {
+    prove_transaction_tracked(input_bytes, &())
+}
+
+/// Executes the transaction like [`prove_transaction`], reporting the phases to the `tracker`.
+pub fn prove_transaction_tracked(
+    input_bytes: &[u8],
+    tracker: &impl CycleTracker,
+) -> ExecutorResult<TransactionPublicValuesStruct> {
+    let input: TransactionInput = track(tracker, Phase::Deserialization, || {
+        bincode::deserialize_from(input_bytes)
+            .map_err(|e| ExecutorError::Other(format!("Unable to decode the input {e}")))
+    })?;
+
+    let TransactionInput {
+        application,
+        consensus,
+        transaction,
+        coinbase_recipient,
+        gas_price,
         storage,
-        ExecutionOptions {
-            extra_tx_checks: true,
-            backtrace: false,
-        },
-    );
+        relayer,
+    } = input;
+    let header_to_produce = PartialBlockHeader {
+        application,
+        consensus,
+    };
 
-    // We don't need artifacts from validation
-    let _ = validator.validate_without_commit(&block)?;
+    let consensus_parameters_version =
+        header_to_produce.application.consensus_parameters_version;
+    let (producer, consensus_parameters) = track(tracker, Phase::StorageSetup, || {
+        let panic_storage = PanicStorage;
+        let storage =
+            StorageTransaction::transaction(panic_storage, ConflictPolicy::Fail, storage);
 
-    // Prepare return values
-    let input_hash = fuel_crypto::Hasher::hash(input_bytes);
-    let block_id = block.header().id();
+        let consensus_parameters =
+            consensus_parameters(&storage, consensus_parameters_version)?;
+        let producer = ExecutionInstance::new(&relayer, storage, execution_options());
+        ExecutorResult::Ok((producer, consensus_parameters))
+    })?;
+    let tx_id = transaction.id(&consensus_parameters.chain_id());
 
-    let proof = PublicValuesStruct {
-        input_hash: U256::from_be_bytes(input_hash.into()),
-        block_id: U256::from_be_bytes(block_id.into()),
+    let components = Components {
+        header_to_produce,
+        transactions_source: OnceTransactionsSource::new(vec![transaction]),
+        coinbase_recipient,
+        gas_price,
     };
+    let result = track(tracker, Phase::Execution, || {
+        producer.produce_without_commit(components, false)
+    })?
+    .into_result();
+
+    if let Some((_, error)) = result
+        .skipped_transactions
+        .into_iter()
+        .find(|(id, _)| *id == tx_id)
+    {
+        return Err(error);
+    }
+
+    let status = result
+        .tx_status
+        .into_iter()
+        .find(|status| status.id == tx_id)
+        .ok_or_else(|| {
+            ExecutorError::Other(format!("The transaction {tx_id} is not executed"))
+        })?;
+    let (status, receipts) = match status.result {
+        TransactionExecutionResult::Success { receipts, .. } => {
+            (TRANSACTION_STATUS_SUCCESS, receipts)
+        }
+        TransactionExecutionResult::Failed { receipts, .. } => {
+            (TRANSACTION_STATUS_FAILURE, receipts)
+        }
+    };
+
+    let input_hash = track(tracker, Phase::InputHashing, || input_hash(input_bytes));
+    let proof = track(tracker, Phase::PublicValues, || {
+        let consensus_parameters_hash = consensus_parameters_hash(&consensus_parameters);
+        TransactionPublicValuesStruct {
+            mode: Mode::Transaction.tag(),
+            input_hash: U256::from_be_bytes(input_hash.into()),
+            tx_id: FixedBytes::from(*tx_id),
+            receipts_root: FixedBytes::from(*receipts_root(&receipts)),
+            status,
+            consensus_parameters_version,
+            consensus_parameters_hash: U256::from_be_bytes(
+                consensus_parameters_hash.into(),
+            ),
+        }
+    });
     Ok(proof)
 }
+
+/// Returns the id of the program whose proofs are aggregated, committed in the
+/// [`AggregatedPublicValuesStruct`]. The `words` are the image id for RISC Zero
+/// and the verifying key hash for SP1.
+pub fn program_id(words: [u32; 8]) -> [u8; 32] {
+    let mut program_id = [0u8; 32];
+    let bytes = words.into_iter().flat_map(u32::to_be_bytes);
+    for (byte, value) in program_id.iter_mut().zip(bytes) {
+        *byte = value;
+    }
+    program_id
+}
+
+/// Aggregates the ABI encoded [`PublicValuesStruct`] of consecutive blocks.
+/// The caller is responsible for verifying proofs of the `public_values`
+/// for the program with the `program_id`. The aggregation guests take the `program_id`
+/// from their input, so the verifier of the aggregated proof must check that the committed
+/// `program_id` is the one of the block guest.
+pub fn aggregate(
+    program_id: [u8; 32],
+    public_values: &[Vec<u8>],
+) -> anyhow::Result<AggregatedPublicValuesStruct> {
+    let public_values = public_values
+        .iter()
+        .map(|bytes| PublicValuesStruct::abi_decode(bytes, true))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| anyhow::anyhow!("Unable to decode the public values {e}"))?;
+
+    let (first, last) = match (public_values.first(), public_values.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => anyhow::bail!("Nothing to aggregate"),
+    };
+
+    for (parent, child) in public_values.iter().zip(public_values.iter().skip(1)) {
+        if child.parent_block_id != parent.block_id {
+            anyhow::bail!(
+                "The block at {} is not a child of the block at {}",
+                child.block_height,
+                parent.block_height
+            );
+        }
+        if parent.block_height.checked_add(1) != Some(child.block_height) {
+            anyhow::bail!(
+                "The block at {} doesn't follow the block at {}",
+                child.block_height,
+                parent.block_height
+            );
+        }
+    }
+
+    let aggregated = AggregatedPublicValuesStruct {
+        program_id: FixedBytes::from(program_id),
+        parent_block_id: first.parent_block_id,
+        block_id: last.block_id,
+        first_block_height: first.block_height,
+        last_block_height: last.block_height,
+        block_ids: public_values
+            .iter()
+            .map(|values| FixedBytes::from(values.block_id.to_be_bytes()))
+            .collect(),
+        input_hashes: public_values
+            .iter()
+            .map(|values| FixedBytes::from(values.input_hash.to_be_bytes()))
+            .collect(),
+        message_outbox_roots: public_values
+            .iter()
+            .map(|values| values.message_outbox_root)
+            .collect(),
+    };
+    Ok(aggregated)
+}
+
+/// Generates the Solidity library with the public values structs and the functions
+/// decoding them, so the contracts decode exactly what the guest programs commit.
+pub fn solidity_library() -> String {
+    let decoders = [
+        PublicValuesStruct::NAME,
+        TransactionPublicValuesStruct::NAME,
+        AggregatedPublicValuesStruct::NAME,
+    ]
+    .map(|name| {
+        format!(
+            "    function decode{function}(bytes memory publicValues)
+        internal
+        pure
+        returns ({name} memory)
+    {{
+        return abi.decode(publicValues, ({name}));
+    }}
+",
+            function = name.trim_end_matches("Struct"),
+        )
+    });
+
+    format!(
+        "{SOLIDITY_HEADER}\n{PUBLIC_VALUES_SOL}\nlibrary PublicValues {{\n{}}}\n",
+        decoders.join("\n")
+    )
+}
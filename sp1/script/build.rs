@@ -1,5 +1,153 @@
-use sp1_helper::build_program_with_args;
+use sha2::{
+    Digest,
+    Sha256,
+};
+use sp1_helper::{
+    build_program_with_args,
+    BuildArgs,
+};
+use sp1_sdk::{
+    CpuProver,
+    HashableKey,
+    Prover,
+};
+use std::{
+    env,
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+const PROGRAM_ELF: &str = "../elf/riscv32im-succinct-zkvm-elf";
+const AGGREGATION_ELF: &str = "../elf/aggregation-elf";
+/// The hash of the program ELF expected by the on-chain verifier and the saved proofs.
+const PROGRAM_ELF_HASH: &str = "../elf/riscv32im-succinct-zkvm-elf.sha256";
+/// Builds the programs in Docker when set to `true`. Only the Docker builds are reproducible,
+/// so the committed hash of the program ELF is enforced only for them.
+const DOCKER_ENV: &str = "SP1_DOCKER";
+/// Skips the build of the programs when set to `true`, the same variable as `sp1-build` reads.
+const SKIP_PROGRAM_BUILD_ENV: &str = "SP1_SKIP_PROGRAM_BUILD";
+/// The tag of the SP1 Docker image, the same as the version of the SDK.
+const DOCKER_TAG: &str = "v2.0.0";
 
 fn main() {
-    build_program_with_args("../program", Default::default())
+    println!("cargo:rerun-if-env-changed={DOCKER_ENV}");
+    println!("cargo:rerun-if-env-changed={SKIP_PROGRAM_BUILD_ENV}");
+    let docker =
+        env::var(DOCKER_ENV).is_ok_and(|docker| docker.eq_ignore_ascii_case("true"));
+    build_program_with_args(
+        "../program",
+        BuildArgs {
+            docker,
+            tag: DOCKER_TAG.to_string(),
+            ..Default::default()
+        },
+    );
+    build_program_with_args(
+        "../aggregation",
+        BuildArgs {
+            docker,
+            tag: DOCKER_TAG.to_string(),
+            elf_name: "aggregation-elf".to_string(),
+            ..Default::default()
+        },
+    );
+
+    // The ELFs are not committed, the binaries include the freshly built ones.
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let program_elf = elf_path(&manifest_dir.join(PROGRAM_ELF));
+    let aggregation_elf = elf_path(&manifest_dir.join(AGGREGATION_ELF));
+    println!("cargo:rustc-env=PROGRAM_ELF={}", program_elf.display());
+    println!(
+        "cargo:rustc-env=AGGREGATION_ELF={}",
+        aggregation_elf.display()
+    );
+
+    // The verification key and the versions are a part of the manifest stored
+    // with every proof, so the verifier can reject proofs of other builds of the program.
+    let elf = fs::read(&program_elf).expect("Unable to read the program ELF");
+    let vkey = if elf.is_empty() {
+        String::new()
+    } else {
+        check_program_hash(&elf, docker);
+        let (_, vk) = CpuProver::new().setup(&elf);
+        vk.bytes32()
+    };
+
+    let prover_manifest = "../prover/Cargo.toml";
+    println!("cargo:rerun-if-changed={prover_manifest}");
+    let prover_manifest: toml::Table = fs::read_to_string(prover_manifest)
+        .expect("Unable to read the prover manifest")
+        .parse()
+        .expect("Unable to parse the prover manifest");
+
+    let prover_version = prover_manifest["package"]["version"]
+        .as_str()
+        .expect("The prover version is a string");
+    let fuel_core_version = prover_manifest["dependencies"]["fuel-core-executor"]
+        ["version"]
+        .as_str()
+        .expect("The fuel-core version is a string");
+
+    println!("cargo:rustc-env=PROGRAM_VKEY={vkey}");
+    println!("cargo:rustc-env=PROVER_VERSION={prover_version}");
+    println!("cargo:rustc-env=FUEL_CORE_VERSION={fuel_core_version}");
+}
+
+/// Returns the path of the built `elf`. If the build of the programs is explicitly skipped
+/// with `SP1_SKIP_PROGRAM_BUILD` on a clean checkout, returns the path of an empty ELF,
+/// so the binaries still compile, but can't prove. Otherwise, a missing ELF fails the build,
+/// because the binaries and the verification key would be empty.
+fn elf_path(elf: &Path) -> PathBuf {
+    if elf.exists() {
+        return elf.to_path_buf();
+    }
+    let skip_program_build = env::var(SKIP_PROGRAM_BUILD_ENV)
+        .is_ok_and(|skip| skip.eq_ignore_ascii_case("true"));
+    if !skip_program_build {
+        panic!(
+            "The ELF {} is not built. Set `{SKIP_PROGRAM_BUILD_ENV}=true` to compile \
+             the binaries without the programs, for example under clippy.",
+            elf.display()
+        );
+    }
+    println!(
+        "cargo:warning=The ELF {} is not built, the binaries can't prove",
+        elf.display()
+    );
+    let file_name = elf.file_name().expect("The ELF path has a file name");
+    let empty_elf =
+        Path::new(&env::var("OUT_DIR").expect("OUT_DIR is set")).join(file_name);
+    fs::write(&empty_elf, []).expect("Unable to write the empty ELF");
+    empty_elf
+}
+
+/// Checks the program `elf` against the committed hash, which pins the program, so a change
+/// of the program or the prover that changes the ELF has to be acknowledged by updating the hash.
+/// Local builds embed the paths of the machine, so only the `docker` builds are checked.
+fn check_program_hash(elf: &[u8], docker: bool) {
+    println!("cargo:rerun-if-changed={PROGRAM_ELF_HASH}");
+    let expected_hash = fs::read_to_string(PROGRAM_ELF_HASH)
+        .expect("Unable to read the committed program ELF hash");
+    let expected_hash = expected_hash.split_whitespace().next().unwrap_or_default();
+    let hash = hex::encode(Sha256::digest(elf));
+    if hash == expected_hash {
+        return;
+    }
+
+    let message = format!(
+        "The program ELF hash {hash} differs from the committed hash {expected_hash}."
+    );
+    if docker {
+        panic!(
+            "{message} If the change of the program is intended, update \
+             `elf/riscv32im-succinct-zkvm-elf.sha256`."
+        );
+    }
+    println!(
+        "cargo:warning={message} Local builds are not reproducible, \
+         the hash is checked for the builds with `{DOCKER_ENV}=true`."
+    );
 }
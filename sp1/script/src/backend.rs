@@ -0,0 +1,68 @@
+//! The SP1 backend of the prover daemon.
+
+use input_provider::daemon::{
+    BlockProof,
+    ProvingBackend,
+};
+use prover::Mode;
+use sp1_sdk::{
+    HashableKey,
+    ProverClient,
+    SP1ProvingKey,
+    SP1Stdin,
+    SP1VerifyingKey,
+};
+
+/// The ELF of the program that proves blocks.
+pub const PROGRAM_ELF: &[u8] = include_bytes!(env!("PROGRAM_ELF"));
+
+/// Proves the blocks with the core proofs,
+/// the proof is the bincode encoded `SP1ProofWithPublicValues`.
+pub struct Sp1Backend {
+    client: ProverClient,
+    pk: SP1ProvingKey,
+    vk: SP1VerifyingKey,
+}
+
+impl Sp1Backend {
+    pub fn new() -> Self {
+        let client = ProverClient::new();
+        let (pk, vk) = client.setup(PROGRAM_ELF);
+        Self { client, pk, vk }
+    }
+}
+
+impl Default for Sp1Backend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProvingBackend for Sp1Backend {
+    fn name(&self) -> &'static str {
+        "sp1"
+    }
+
+    fn vkey(&self) -> Option<String> {
+        Some(self.vk.bytes32())
+    }
+
+    fn prove(&self, input: &[u8]) -> anyhow::Result<BlockProof> {
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&Mode::Validation);
+        stdin.write_vec(input.to_vec());
+
+        // The proof doesn't report the cycles, so the program is executed first.
+        let (_, report) = self.client.execute(PROGRAM_ELF, stdin.clone()).run()?;
+        let proof = self.client.prove(&self.pk, stdin).run()?;
+        self.client
+            .verify(&proof, &self.vk)
+            .map_err(|e| anyhow::anyhow!("Proof verification failed: {e}"))?;
+
+        Ok(BlockProof {
+            public_values: proof.public_values.to_vec(),
+            proof: bincode::serialize(&proof)?,
+            cycles: Some(report.total_instruction_count()),
+        })
+    }
+}
@@ -0,0 +1,142 @@
+//! Proves consecutive blocks with the block program and aggregates their proofs
+//! into one proof with the aggregation program.
+//!
+//! You can run this script using the following command:
+//! ```shell
+//! RUST_LOG=info cargo run --release --bin aggregate -- --blocks 3
+//! ```
+
+use alloy_sol_types::SolType;
+use clap::Parser;
+use fuel_script::{
+    backend::PROGRAM_ELF,
+    chain_args::ChainArgs,
+};
+use input_provider::{
+    start_node_with_workloads_and_produce_prover_inputs,
+    workload::Workload,
+};
+use prover::{
+    AggregatedPublicValuesStruct,
+    Mode,
+};
+use sp1_sdk::{
+    HashableKey,
+    ProverClient,
+    SP1Proof,
+    SP1Stdin,
+};
+
+/// The ELF of the program that aggregates proofs of blocks.
+pub const AGGREGATION_ELF: &[u8] = include_bytes!(env!("AGGREGATION_ELF"));
+
+/// The arguments for the command.
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    /// The number of consecutive blocks to prove and aggregate.
+    #[clap(long, default_value = "2")]
+    blocks: usize,
+
+    #[clap(flatten)]
+    chain: ChainArgs,
+
+    /// The kind of transactions included into the proven blocks.
+    #[clap(long, default_value_t = Workload::Script)]
+    workload: Workload,
+
+    /// Produce blocks with fixed timestamps to get a reproducible input.
+    #[clap(long)]
+    deterministic: bool,
+}
+
+#[tokio::main]
+async fn main() {
+    // Setup the logger.
+    sp1_sdk::utils::setup_logger();
+
+    // Parse the command line arguments.
+    let args = Args::parse();
+
+    let mut node_config = args.chain.node_config().unwrap();
+    if args.deterministic {
+        node_config = node_config.with_deterministic_block_production();
+    }
+
+    let workloads = vec![args.workload; args.blocks];
+    let service =
+        start_node_with_workloads_and_produce_prover_inputs(node_config, &workloads)
+            .await
+            .unwrap();
+
+    // Setup the prover client.
+    let client = ProverClient::new();
+
+    // Setup the programs.
+    let (block_pk, block_vk) = client.setup(PROGRAM_ELF);
+    let (aggregation_pk, aggregation_vk) = client.setup(AGGREGATION_ELF);
+
+    // The aggregation program verifies block proofs, so they must be compressed.
+    let proofs = service
+        .inputs
+        .iter()
+        .map(|input| {
+            let mut stdin = SP1Stdin::new();
+            stdin.write(&Mode::Validation);
+            stdin.write(input);
+            client
+                .prove(&block_pk, stdin)
+                .compressed()
+                .run()
+                .expect("failed to generate block proof")
+        })
+        .collect::<Vec<_>>();
+    println!("Proved {} blocks", proofs.len());
+
+    // Setup the inputs of the aggregation program.
+    let mut stdin = SP1Stdin::new();
+    stdin.write(&block_vk.hash_u32());
+    let public_values = proofs
+        .iter()
+        .map(|proof| proof.public_values.to_vec())
+        .collect::<Vec<_>>();
+    stdin.write(&public_values);
+    for proof in proofs {
+        let SP1Proof::Compressed(proof) = proof.proof else {
+            panic!("The block proof is not compressed");
+        };
+        stdin.write_proof(proof, block_vk.vk.clone());
+    }
+
+    // Generate the aggregated proof.
+    let proof = client
+        .prove(&aggregation_pk, stdin)
+        .compressed()
+        .run()
+        .expect("failed to generate aggregated proof");
+
+    let aggregated =
+        AggregatedPublicValuesStruct::abi_decode(proof.public_values.as_slice(), true)
+            .unwrap();
+
+    // The aggregation program verifies the proofs against the key from its input.
+    assert_eq!(
+        aggregated.program_id.0,
+        prover::program_id(block_vk.hash_u32())
+    );
+    println!(
+        "Aggregated blocks: {}..={}",
+        aggregated.first_block_height, aggregated.last_block_height
+    );
+    println!(
+        "Aggregated parent block id: {:?}",
+        aggregated.parent_block_id
+    );
+    println!("Aggregated block id: {:?}", aggregated.block_id);
+
+    // Verify the proof.
+    client
+        .verify(&proof, &aggregation_vk)
+        .expect("failed to verify aggregated proof");
+    println!("Successfully verified aggregated proof!");
+}
@@ -0,0 +1,155 @@
+//! Runs the prover daemon that proves every new block of the node with SP1.
+//!
+//! The node database and the proved blocks are saved into the directory:
+//! ```shell
+//! RUST_LOG=info cargo run --release --bin daemon -- <DIR> --workload script
+//! ```
+//! The `--workload` is sent every `--interval` seconds to produce blocks, otherwise the blocks
+//! are produced by the transactions sent to the node. With `--api <ADDR>`, the daemon serves
+//! the HTTP API to request and fetch the proofs, see [`input_provider::api`].
+//!
+//! To prove the blocks on many machines, pass `--coordinator <ADDR>` and `--parallel-jobs <N>`,
+//! and run the `worker` binary on every machine.
+
+use clap::Parser;
+use fuel_script::{
+    backend::Sp1Backend,
+    chain_args::ChainArgs,
+};
+use input_provider::{
+    api,
+    coordinator::Coordinator,
+    daemon::{
+        DaemonConfig,
+        ProverDaemon,
+        ProvingBackend,
+    },
+    start_node,
+    transaction_sender,
+    workload::{
+        TransactionSender,
+        Workload,
+    },
+};
+use std::{
+    net::SocketAddr,
+    path::PathBuf,
+    time::Duration,
+};
+
+/// The arguments for the command.
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    /// The directory for the node database and the proved blocks.
+    dir: PathBuf,
+
+    #[clap(flatten)]
+    chain: ChainArgs,
+
+    /// The number of attempts to prove the block before giving up.
+    #[clap(long, default_value_t = 3)]
+    max_attempts: u32,
+
+    /// The kind of transactions sent to the node to produce blocks.
+    #[clap(long)]
+    workload: Option<Workload>,
+
+    /// The interval in seconds between the workloads.
+    #[clap(long, default_value_t = 10)]
+    interval: u64,
+
+    /// The address to serve the HTTP API on, for example `127.0.0.1:8080`.
+    #[clap(long)]
+    api: Option<SocketAddr>,
+
+    /// The address to listen for the workers on, for example `0.0.0.0:9000`.
+    /// The blocks are proved by the workers instead of the local prover.
+    #[clap(long)]
+    coordinator: Option<SocketAddr>,
+
+    /// The number of blocks proved at once.
+    #[clap(long, default_value_t = 1)]
+    parallel_jobs: usize,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    // Setup the logger.
+    sp1_sdk::utils::setup_logger();
+
+    // Parse the command line arguments.
+    let args = Args::parse();
+
+    let mut node_config = args.chain.node_config()?;
+    if let Some(workload) = args.workload {
+        node_config = workload.prepare(node_config)?;
+    }
+
+    let fuel_node = start_node(&node_config, &args.dir.join("db")).await?;
+    println!("The node is listening on {}", fuel_node.bound_address);
+
+    let sender = match args.workload {
+        Some(workload) => {
+            Some((transaction_sender(&fuel_node, &node_config).await, workload))
+        }
+        None => None,
+    };
+    let config = DaemonConfig::new(args.dir.join("blocks"))
+        .with_max_attempts(args.max_attempts)
+        .with_parallel_jobs(args.parallel_jobs);
+    let backend = Sp1Backend::new();
+    match args.coordinator {
+        Some(address) => {
+            let coordinator = Coordinator::new(&backend);
+            let listener = tokio::net::TcpListener::bind(address).await?;
+            tokio::spawn(coordinator.clone().serve(listener));
+            let daemon = ProverDaemon::new(&fuel_node, coordinator, config)?;
+            follow_chain(daemon, sender, &args).await
+        }
+        None => {
+            let daemon = ProverDaemon::new(&fuel_node, backend, config)?;
+            follow_chain(daemon, sender, &args).await
+        }
+    }
+}
+
+/// Proves the new blocks with the `daemon` until Ctrl-C,
+/// while the `sender` sends its workload every interval.
+async fn follow_chain<B: ProvingBackend>(
+    daemon: ProverDaemon<'_, B>,
+    sender: Option<(TransactionSender, Workload)>,
+    args: &Args,
+) -> anyhow::Result<()> {
+    let shutdown = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+
+    let send_workloads = async {
+        let Some((sender, workload)) = sender else {
+            return std::future::pending::<()>().await;
+        };
+        loop {
+            if let Err(e) = sender.send(workload).await {
+                eprintln!("Unable to send the workload: {e}");
+            }
+            tokio::time::sleep(Duration::from_secs(args.interval)).await;
+        }
+    };
+
+    let handle = daemon.handle();
+    let api_address = args.api;
+    let serve_api = async move {
+        let Some(address) = api_address else {
+            return std::future::pending::<anyhow::Result<()>>().await;
+        };
+        let listener = tokio::net::TcpListener::bind(address).await?;
+        api::serve(listener, handle).await
+    };
+
+    tokio::select! {
+        result = daemon.run(shutdown) => result,
+        _ = send_workloads => Ok(()),
+        result = serve_api => result,
+    }
+}
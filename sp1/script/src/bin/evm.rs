@@ -9,18 +9,26 @@
 //! ```shell
 //! RUST_LOG=info cargo run --release --bin evm -- --system plonk
 //! ```
+//!
+//! The fixture of the proof is saved as `sp1-<SYSTEM>-<BLOCK_ID>.json` into `--fixtures-dir`.
 
-use alloy_sol_types::SolType;
 use clap::{
     Parser,
     ValueEnum,
 };
-use input_provider::start_node_with_transaction_and_produce_prover_input;
-use prover::PublicValuesStruct;
-use serde::{
-    Deserialize,
-    Serialize,
+use fuel_script::{
+    backend::PROGRAM_ELF,
+    chain_args::ChainArgs,
+};
+use input_provider::{
+    fixture::{
+        ProofFixture,
+        Zkvm,
+    },
+    start_node_with_workload_and_produce_prover_input,
+    workload::Workload,
 };
+use prover::Mode;
 use sp1_sdk::{
     HashableKey,
     ProverClient,
@@ -28,11 +36,10 @@ use sp1_sdk::{
     SP1Stdin,
     SP1VerifyingKey,
 };
-use std::path::PathBuf;
-
-/// The ELF (executable and linkable format) file for the Succinct RISC-V zkVM.
-pub const FIBONACCI_ELF: &[u8] =
-    include_bytes!("../../../elf/riscv32im-succinct-zkvm-elf");
+use std::path::{
+    Path,
+    PathBuf,
+};
 
 /// The arguments for the EVM command.
 #[derive(Parser, Debug)]
@@ -42,6 +49,22 @@ struct EVMArgs {
     n: u32,
     #[clap(long, value_enum, default_value = "groth16")]
     system: ProofSystem,
+
+    #[clap(flatten)]
+    chain: ChainArgs,
+
+    /// The kind of transactions included into the proven block.
+    #[clap(long, default_value_t = Workload::Script)]
+    workload: Workload,
+
+    /// Produce blocks with fixed timestamps to get a reproducible input.
+    #[clap(long)]
+    deterministic: bool,
+
+    /// The directory to save the fixture of the proof.
+    /// The fixtures of the contracts by default.
+    #[clap(long)]
+    fixtures_dir: Option<PathBuf>,
 }
 
 /// Enum representing the available proof systems
@@ -51,37 +74,33 @@ enum ProofSystem {
     Groth16,
 }
 
-/// A fixture that can be used to test the verification of SP1 zkVM proofs inside Solidity.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct SP1FibonacciProofFixture {
-    block_id: [u8; 32],
-    input_hash: [u8; 32],
-    vkey: String,
-    public_values: String,
-    proof: String,
-}
-
 #[tokio::main]
 async fn main() {
     // Setup the logger.
     sp1_sdk::utils::setup_logger();
 
-    let service = start_node_with_transaction_and_produce_prover_input()
-        .await
-        .unwrap();
-
     // Parse the command line arguments.
     let args = EVMArgs::parse();
 
+    let mut node_config = args.chain.node_config().unwrap();
+    if args.deterministic {
+        node_config = node_config.with_deterministic_block_production();
+    }
+
+    let service =
+        start_node_with_workload_and_produce_prover_input(node_config, args.workload)
+            .await
+            .unwrap();
+
     // Setup the prover client.
     let client = ProverClient::new();
 
     // Setup the program.
-    let (pk, vk) = client.setup(FIBONACCI_ELF);
+    let (pk, vk) = client.setup(PROGRAM_ELF);
 
     // Setup the inputs.
     let mut stdin = SP1Stdin::new();
+    stdin.write(&Mode::Validation);
     stdin.write(&service.input);
 
     println!("Proof System: {:?}", args.system);
@@ -93,30 +112,28 @@ async fn main() {
     }
     .expect("failed to generate proof");
 
-    create_proof_fixture(&proof, &vk, args.system);
+    let fixtures_dir = args.fixtures_dir.unwrap_or_else(|| {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../contracts/src/fixtures")
+    });
+    create_proof_fixture(&proof, &vk, args.system, &fixtures_dir);
 }
 
-/// Create a fixture for the given proof.
+/// Create a fixture for the given proof in the `fixtures_dir`.
 fn create_proof_fixture(
     proof: &SP1ProofWithPublicValues,
     vk: &SP1VerifyingKey,
     system: ProofSystem,
+    fixtures_dir: &Path,
 ) {
-    // Deserialize the public values.
-    let bytes = proof.public_values.as_slice();
-    let PublicValuesStruct {
-        input_hash,
-        block_id,
-    } = PublicValuesStruct::abi_decode(bytes, false).unwrap();
-
     // Create the testing fixture so we can test things end-to-end.
-    let fixture = SP1FibonacciProofFixture {
-        block_id: block_id.to_be_bytes(),
-        input_hash: input_hash.to_be_bytes(),
-        vkey: vk.bytes32().to_string(),
-        public_values: format!("0x{}", hex::encode(bytes)),
-        proof: format!("0x{}", hex::encode(proof.bytes())),
-    };
+    let fixture = ProofFixture::new(
+        Zkvm::Sp1,
+        &format!("{:?}", system),
+        vk.bytes32().to_string(),
+        proof.public_values.as_slice(),
+        &proof.bytes(),
+    )
+    .expect("failed to create fixture");
 
     // The verification key is used to verify that the proof corresponds to the execution of the
     // program on the given input.
@@ -135,12 +152,6 @@ fn create_proof_fixture(
     println!("Proof Bytes: {}", fixture.proof);
 
     // Save the fixture to a file.
-    let fixture_path =
-        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../contracts/src/fixtures");
-    std::fs::create_dir_all(&fixture_path).expect("failed to create fixture path");
-    std::fs::write(
-        fixture_path.join(format!("{:?}-fixture.json", system).to_lowercase()),
-        serde_json::to_string_pretty(&fixture).unwrap(),
-    )
-    .expect("failed to write fixture");
+    let fixture_path = fixture.save(fixtures_dir).expect("failed to write fixture");
+    println!("Saved the fixture to {}", fixture_path.display());
 }
@@ -9,19 +9,50 @@
 //! ```shell
 //! RUST_LOG=info cargo run --release -- --prove
 //! ```
+//!
+//! With `--execute`, the cycles of every phase of proving are printed from the cycle tracker
+//! of the program, see [`input_provider::profile`].
+//!
+//! With `--transaction`, the program executes a transfer from the wallet in the context of the
+//! next block without including it into a block, and commits the transaction id, receipts root
+//! and status:
+//! ```shell
+//! RUST_LOG=info cargo run --release -- --execute --transaction
+//! ```
+//!
+//! With `--output <DIR>`, the proof is saved with its input and public values, see
+//! [`fuel_script::proof_files`] for the format. The saved proof can be verified later with:
+//! ```shell
+//! cargo run --release --bin verify -- <DIR>
+//! ```
 
 use alloy_sol_types::SolType;
 use clap::Parser;
-use input_provider::start_node_with_transaction_and_produce_prover_input;
-use prover::PublicValuesStruct;
+use fuel_script::{
+    backend::PROGRAM_ELF,
+    chain_args::ChainArgs,
+    manifest::Manifest,
+    proof_files::ProofFiles,
+};
+use input_provider::{
+    produce_prover_production_input_for_block,
+    profile::CycleReport,
+    start_node_and_produce_prover_transaction_input,
+    start_node_with_workload_and_produce_prover_input,
+    workload::Workload,
+    NodeConfig,
+};
+use prover::{
+    Mode,
+    PublicValuesStruct,
+    TransactionPublicValuesStruct,
+};
 use sp1_sdk::{
+    HashableKey,
     ProverClient,
     SP1Stdin,
 };
-
-/// The ELF (executable and linkable format) file for the Succinct RISC-V zkVM.
-pub const FIBONACCI_ELF: &[u8] =
-    include_bytes!("../../../elf/riscv32im-succinct-zkvm-elf");
+use std::path::PathBuf;
 
 /// The arguments for the command.
 #[derive(Parser, Debug)]
@@ -35,6 +66,30 @@ struct Args {
 
     #[clap(long, default_value = "20")]
     n: u32,
+
+    #[clap(flatten)]
+    chain: ChainArgs,
+
+    /// The kind of transactions included into the proven block.
+    #[clap(long, default_value_t = Workload::Script)]
+    workload: Workload,
+
+    /// Produce blocks with fixed timestamps to get a reproducible input.
+    #[clap(long)]
+    deterministic: bool,
+
+    /// Prove the production of the block from its header and transactions
+    /// instead of the validation of the block.
+    #[clap(long)]
+    production: bool,
+
+    /// Prove the execution of one transaction instead of the block.
+    #[clap(long)]
+    transaction: bool,
+
+    /// The directory to save the proof with its input and public values.
+    #[clap(long)]
+    output: Option<PathBuf>,
 }
 
 #[tokio::main]
@@ -42,10 +97,6 @@ async fn main() {
     // Setup the logger.
     sp1_sdk::utils::setup_logger();
 
-    let service = start_node_with_transaction_and_produce_prover_input()
-        .await
-        .unwrap();
-
     // Parse the command line arguments.
     let args = Args::parse();
 
@@ -54,16 +105,46 @@ async fn main() {
         std::process::exit(1);
     }
 
+    let mut node_config = args.chain.node_config().unwrap();
+    if args.deterministic {
+        node_config = node_config.with_deterministic_block_production();
+    }
+
+    if args.transaction {
+        prove_transaction(node_config, args.execute).await;
+        return;
+    }
+
+    let service =
+        start_node_with_workload_and_produce_prover_input(node_config, args.workload)
+            .await
+            .unwrap();
+
     // Setup the prover client.
     let client = ProverClient::new();
 
     // Setup the inputs.
+    let (mode, input) = if args.production {
+        let production_input = produce_prover_production_input_for_block(
+            &service.fuel_node,
+            &service.input.block,
+        )
+        .unwrap();
+        let input = bincode::serialize(&production_input)
+            .expect("Failed to serialize production input");
+        (Mode::Production, input)
+    } else {
+        let input = bincode::serialize(&service.input)
+            .expect("Failed to serialize service input");
+        (Mode::Validation, input)
+    };
     let mut stdin = SP1Stdin::new();
-    stdin.write(&service.input);
+    stdin.write(&mode);
+    stdin.write_vec(input.clone());
 
     if args.execute {
         // Execute the program
-        let (output, report) = client.execute(FIBONACCI_ELF, stdin).run().unwrap();
+        let (output, report) = client.execute(PROGRAM_ELF, stdin).run().unwrap();
         println!("Program executed successfully.");
 
         // Read the output.
@@ -74,12 +155,34 @@ async fn main() {
 
         println!("Proof block id: {:?}", proof.block_id);
         println!("Proof input hash: {:?}", proof.input_hash);
+        println!(
+            "Proof consensus parameters version: {}",
+            proof.consensus_parameters_version
+        );
+        println!(
+            "Proof consensus parameters hash: {:?}",
+            proof.consensus_parameters_hash
+        );
+        println!(
+            "Proof processed relayed transactions: {:?}",
+            proof.processed_relayed_transactions
+        );
+        println!(
+            "Proof failed relayed transactions: {:?}",
+            proof.failed_relayed_transactions
+        );
+        println!("Proof message outbox root: {:?}", proof.message_outbox_root);
 
         // Record the number of cycles executed.
         println!("Number of cycles: {}", report.total_instruction_count());
+        let cycle_report = CycleReport::from_cycle_tracker(
+            &report.cycle_tracker,
+            report.total_instruction_count(),
+        );
+        println!("{cycle_report}");
     } else {
         // Setup the program for proving.
-        let (pk, vk) = client.setup(FIBONACCI_ELF);
+        let (pk, vk) = client.setup(PROGRAM_ELF);
 
         // Generate the proof
         let proof = client
@@ -92,5 +195,63 @@ async fn main() {
         // Verify the proof.
         client.verify(&proof, &vk).expect("failed to verify proof");
         println!("Successfully verified proof!");
+
+        if let Some(dir) = &args.output {
+            println!("Verification Key: {}", vk.bytes32());
+            let proof_files = ProofFiles {
+                manifest: Manifest::current(),
+                proof,
+                vkey: vk,
+                input,
+            };
+            proof_files.save(dir).expect("failed to save proof");
+            println!("Saved the proof to {}", dir.display());
+        }
     }
 }
+
+/// Proves the transfer from the wallet in the context of the next block
+/// with the [`Mode::Transaction`]. With `execute`, the program is executed without proving.
+async fn prove_transaction(node_config: NodeConfig, execute: bool) {
+    let service = start_node_and_produce_prover_transaction_input(node_config)
+        .await
+        .unwrap();
+    let input = bincode::serialize(&service.input)
+        .expect("Failed to serialize transaction input");
+
+    let client = ProverClient::new();
+    let mut stdin = SP1Stdin::new();
+    stdin.write(&Mode::Transaction);
+    stdin.write_vec(input);
+
+    let public_values = if execute {
+        let (output, report) = client.execute(PROGRAM_ELF, stdin).run().unwrap();
+        println!("Program executed successfully.");
+
+        println!("Number of cycles: {}", report.total_instruction_count());
+        let cycle_report = CycleReport::from_cycle_tracker(
+            &report.cycle_tracker,
+            report.total_instruction_count(),
+        );
+        println!("{cycle_report}");
+        output.as_slice().to_vec()
+    } else {
+        let (pk, vk) = client.setup(PROGRAM_ELF);
+        let proof = client
+            .prove(&pk, stdin)
+            .run()
+            .expect("failed to generate proof");
+        println!("Successfully generated proof!");
+
+        client.verify(&proof, &vk).expect("failed to verify proof");
+        println!("Successfully verified proof!");
+        proof.public_values.as_slice().to_vec()
+    };
+
+    let proof = TransactionPublicValuesStruct::abi_decode(&public_values, true).unwrap();
+    assert_eq!(proof.tx_id.0, *service.tx_id);
+
+    println!("Proof transaction id: {:?}", proof.tx_id);
+    println!("Proof receipts root: {:?}", proof.receipts_root);
+    println!("Proof transaction status: {}", proof.status);
+}
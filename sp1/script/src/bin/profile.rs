@@ -0,0 +1,66 @@
+//! Executes the program on the saved input and writes the trace of the execution, to find
+//! the hot functions of the FuelVM and the executor.
+//!
+//! You can run this script using the following command:
+//! ```shell
+//! TRACE_FILE=trace.log RUST_LOG=info cargo run --release --bin profile -- <DIR>/input.bin
+//! ```
+//! The executor reads `TRACE_FILE` from the environment, so it's exported before the script
+//! starts instead of being passed as an argument.
+//! The trace contains the program counter of every cycle and is turned into the cycles
+//! by function with the symbols of the program ELF:
+//! ```shell
+//! cargo prove trace --elf ../elf/riscv32im-succinct-zkvm-elf --trace trace.log
+//! ```
+
+use clap::Parser;
+use fuel_script::backend::PROGRAM_ELF;
+use prover::Mode;
+use sp1_sdk::{
+    ProverClient,
+    SP1Stdin,
+};
+use std::path::PathBuf;
+
+/// The arguments for the command.
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    /// The bincode encoded prover input, for example `input.bin` saved with `--output`.
+    input: PathBuf,
+
+    /// The input is for the production of the block instead of the validation.
+    #[clap(long)]
+    production: bool,
+}
+
+fn main() -> anyhow::Result<()> {
+    // Setup the logger.
+    sp1_sdk::utils::setup_logger();
+
+    // Parse the command line arguments.
+    let args = Args::parse();
+    let trace_file = std::env::var_os("TRACE_FILE").ok_or_else(|| {
+        anyhow::anyhow!("Set `TRACE_FILE` to the file to write the trace to")
+    })?;
+
+    let mode = if args.production {
+        Mode::Production
+    } else {
+        Mode::Validation
+    };
+    let input = std::fs::read(&args.input)?;
+    let mut stdin = SP1Stdin::new();
+    stdin.write(&mode);
+    stdin.write_vec(input);
+
+    let client = ProverClient::new();
+    let (_, report) = client
+        .execute(PROGRAM_ELF, stdin)
+        .run()
+        .map_err(|e| anyhow::anyhow!("Unable to execute the program: {e}"))?;
+
+    println!("Number of cycles: {}", report.total_instruction_count());
+    println!("Saved the trace to {}", PathBuf::from(trace_file).display());
+    Ok(())
+}
@@ -0,0 +1,75 @@
+//! Verifies the proof saved by the `fuel` binary with `--output`.
+//!
+//! You can run this script using the following command:
+//! ```shell
+//! RUST_LOG=info cargo run --release --bin verify -- <DIR>
+//! ```
+
+use alloy_sol_types::SolType;
+use clap::Parser;
+use fuel_script::{
+    manifest::Manifest,
+    proof_files::ProofFiles,
+};
+use prover::PublicValuesStruct;
+use sp1_sdk::{
+    HashableKey,
+    ProverClient,
+};
+use std::path::PathBuf;
+
+/// The arguments for the command.
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    /// The directory with the saved proof.
+    proof: PathBuf,
+
+    /// The expected verification key of the program, as printed when the proof is saved.
+    /// Defaults to the verification key of the program embedded into this binary.
+    #[clap(long)]
+    vkey: Option<String>,
+}
+
+fn main() -> anyhow::Result<()> {
+    // Setup the logger.
+    sp1_sdk::utils::setup_logger();
+
+    // Parse the command line arguments.
+    let args = Args::parse();
+
+    let proof_files = ProofFiles::load(&args.proof)?;
+
+    // Reject proofs of other builds of the program.
+    let current_manifest = Manifest::current();
+    let expected_manifest = Manifest {
+        vkey: args.vkey.unwrap_or(current_manifest.vkey.clone()),
+        ..current_manifest
+    };
+    proof_files.manifest.check(&expected_manifest)?;
+    anyhow::ensure!(
+        proof_files.vkey.bytes32() == expected_manifest.vkey,
+        "The saved verification key {} doesn't match the manifest",
+        proof_files.vkey.bytes32()
+    );
+
+    // Verify the proof.
+    let client = ProverClient::new();
+    client
+        .verify(&proof_files.proof, &proof_files.vkey)
+        .map_err(|e| anyhow::anyhow!("Proof verification failed: {e}"))?;
+
+    // Check that the proof was generated for the saved input.
+    let public_values =
+        PublicValuesStruct::abi_decode(proof_files.proof.public_values.as_slice(), true)?;
+    let input_hash: [u8; 32] = prover::input_hash(&proof_files.input).into();
+    anyhow::ensure!(
+        public_values.input_hash.to_be_bytes() == input_hash,
+        "The saved input doesn't match the committed input hash"
+    );
+
+    println!("Proof block id: {:?}", public_values.block_id);
+    println!("Proof block height: {}", public_values.block_height);
+    println!("Successfully verified proof!");
+    Ok(())
+}
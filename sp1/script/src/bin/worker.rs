@@ -0,0 +1,34 @@
+//! Runs the worker that proves the blocks with SP1 for the daemon started with `--coordinator`.
+//!
+//! ```shell
+//! RUST_LOG=info cargo run --release --bin worker -- <COORDINATOR_ADDR>
+//! ```
+//! The worker registers with the verification key of the program, so the coordinator
+//! accepts only the workers built from the same program.
+
+use clap::Parser;
+use fuel_script::backend::Sp1Backend;
+use input_provider::worker::ProvingWorker;
+
+/// The arguments for the command.
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    /// The address of the coordinator, for example `10.0.0.1:9000`.
+    coordinator: String,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    // Setup the logger.
+    sp1_sdk::utils::setup_logger();
+
+    // Parse the command line arguments.
+    let args = Args::parse();
+
+    ProvingWorker::new(Sp1Backend::new())
+        .run(args.coordinator.as_str())
+        .await?;
+    println!("The coordinator is stopped");
+    Ok(())
+}
@@ -0,0 +1,40 @@
+//! The arguments that select the chain of the node shared by the binaries.
+
+use input_provider::NodeConfig;
+use std::path::PathBuf;
+
+/// The chain of the node that produces the proven blocks.
+#[derive(clap::Args, Debug)]
+pub struct ChainArgs {
+    /// The chain snapshot directory in the `fuel-core` format.
+    /// The local testnet is used by default.
+    #[clap(long, env = "FUEL_SNAPSHOT")]
+    pub snapshot: Option<PathBuf>,
+
+    /// The state config JSON file that overrides the state of the chain,
+    /// in the format of the `state_config.json` of the snapshot.
+    #[clap(long)]
+    pub state_config: Option<PathBuf>,
+
+    /// The hex encoded secret key of the wallet that pays for the transactions.
+    /// It must own coins in the state of the chain. A local testnet wallet by default.
+    #[clap(long, env = "FUEL_WALLET_SECRET")]
+    pub wallet_secret: Option<String>,
+}
+
+impl ChainArgs {
+    /// Returns the node config for the chain.
+    pub fn node_config(&self) -> anyhow::Result<NodeConfig> {
+        let mut node_config = NodeConfig::default();
+        if let Some(snapshot) = &self.snapshot {
+            node_config = node_config.with_snapshot(snapshot);
+        }
+        if let Some(state_config) = &self.state_config {
+            node_config = node_config.with_state_config_file(state_config)?;
+        }
+        if let Some(wallet_secret) = &self.wallet_secret {
+            node_config = node_config.with_wallet_secret_hex(wallet_secret)?;
+        }
+        Ok(node_config)
+    }
+}
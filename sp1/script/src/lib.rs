@@ -0,0 +1,6 @@
+//! The code shared by the script binaries.
+
+pub mod backend;
+pub mod chain_args;
+pub mod manifest;
+pub mod proof_files;
@@ -0,0 +1,54 @@
+//! The description of the program build that generated the proof.
+//!
+//! The manifest is stored with every proof as `manifest.json`. The verifier compares it
+//! with the manifest of its own build and rejects proofs of other programs.
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    /// The verification key hash of the program.
+    pub vkey: String,
+    /// The version of the `prover` crate. It's bumped when the public values or their encoding
+    /// change, so the proofs with the old journals are rejected before they are decoded.
+    pub prover_version: String,
+    /// The version of `fuel-core` used by the prover.
+    pub fuel_core_version: String,
+}
+
+impl Manifest {
+    /// Returns the manifest of the program embedded into this binary.
+    pub fn current() -> Self {
+        Self {
+            vkey: env!("PROGRAM_VKEY").to_string(),
+            prover_version: env!("PROVER_VERSION").to_string(),
+            fuel_core_version: env!("FUEL_CORE_VERSION").to_string(),
+        }
+    }
+
+    /// Checks that the proof with this manifest was generated by the `expected` program.
+    pub fn check(&self, expected: &Manifest) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            self.vkey == expected.vkey,
+            "The proof was generated by the program with verification key {}, but {} is expected",
+            self.vkey,
+            expected.vkey
+        );
+        anyhow::ensure!(
+            self.prover_version == expected.prover_version,
+            "The proof was generated with the prover {}, but {} is expected",
+            self.prover_version,
+            expected.prover_version
+        );
+        anyhow::ensure!(
+            self.fuel_core_version == expected.fuel_core_version,
+            "The proof was generated with fuel-core {}, but {} is expected",
+            self.fuel_core_version,
+            expected.fuel_core_version
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,77 @@
+//! Persistence of proofs, so proving and verification can happen on different machines
+//! and at different times.
+//!
+//! The proof is stored in a directory with the files:
+//! - `proof.bin` is the `SP1ProofWithPublicValues` saved with its `save` method;
+//! - `vkey.bin` is the bincode encoded `SP1VerifyingKey` of the program;
+//! - `input.bin` is the bincode encoded prover input the proof was generated for, its hash is
+//!   committed as the `input_hash`;
+//! - `public_values.bin` is the ABI encoded `PublicValuesStruct` committed by the program;
+//! - `manifest.json` is the [`Manifest`] of the program build that generated the proof.
+
+use crate::manifest::Manifest;
+use anyhow::Context;
+use sp1_sdk::{
+    SP1ProofWithPublicValues,
+    SP1VerifyingKey,
+};
+use std::{
+    fs,
+    path::Path,
+};
+
+const PROOF_FILE: &str = "proof.bin";
+const VKEY_FILE: &str = "vkey.bin";
+const INPUT_FILE: &str = "input.bin";
+const PUBLIC_VALUES_FILE: &str = "public_values.bin";
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// The proof with the verifying key and the input.
+pub struct ProofFiles {
+    pub manifest: Manifest,
+    pub proof: SP1ProofWithPublicValues,
+    pub vkey: SP1VerifyingKey,
+    pub input: Vec<u8>,
+}
+
+impl ProofFiles {
+    /// Saves the proof into the `dir`, creating it if it doesn't exist.
+    pub fn save(&self, dir: &Path) -> anyhow::Result<()> {
+        fs::create_dir_all(dir).with_context(|| {
+            format!("Unable to create the directory {}", dir.display())
+        })?;
+        self.proof.save(dir.join(PROOF_FILE))?;
+        fs::write(dir.join(VKEY_FILE), bincode::serialize(&self.vkey)?)?;
+        fs::write(dir.join(INPUT_FILE), &self.input)?;
+        fs::write(
+            dir.join(PUBLIC_VALUES_FILE),
+            self.proof.public_values.as_slice(),
+        )?;
+        fs::write(
+            dir.join(MANIFEST_FILE),
+            serde_json::to_string_pretty(&self.manifest)?,
+        )?;
+        Ok(())
+    }
+
+    /// Loads the proof saved with [`ProofFiles::save`] from the `dir`.
+    pub fn load(dir: &Path) -> anyhow::Result<Self> {
+        let read = |file: &str| {
+            let path = dir.join(file);
+            fs::read(&path).with_context(|| format!("Unable to read {}", path.display()))
+        };
+        let manifest = serde_json::from_slice(&read(MANIFEST_FILE)?)
+            .context("Unable to decode the manifest")?;
+        let proof = SP1ProofWithPublicValues::load(dir.join(PROOF_FILE))
+            .context("Unable to load the proof")?;
+        let vkey = bincode::deserialize(&read(VKEY_FILE)?)
+            .context("Unable to decode the verifying key")?;
+
+        Ok(Self {
+            manifest,
+            proof,
+            vkey,
+            input: read(INPUT_FILE)?,
+        })
+    }
+}
@@ -0,0 +1,21 @@
+use std::{
+    env,
+    process::Command,
+};
+
+fn main() {
+    // The harness executes the bytecode of the contracts compiled by Foundry,
+    // only when it's built with the `forge` feature.
+    println!("cargo:rerun-if-changed=build.rs");
+    if env::var_os("CARGO_FEATURE_FORGE").is_none() {
+        return;
+    }
+
+    println!("cargo:rerun-if-changed=../contracts/src");
+    println!("cargo:rerun-if-changed=../contracts/foundry.toml");
+    let status = Command::new("forge")
+        .args(["build", "--root", "../contracts"])
+        .status()
+        .expect("Unable to run `forge`, install Foundry to build the contracts");
+    assert!(status.success(), "Unable to build the contracts");
+}
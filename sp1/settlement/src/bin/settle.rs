@@ -0,0 +1,85 @@
+//! Settles the proofs of consecutive blocks in the local EVM.
+//!
+//! The proofs are the fixtures exported by the SP1 `evm` binary or the RISC Zero host,
+//! passed in the order of the blocks:
+//! ```shell
+//! cargo run --release -p settlement --features forge --bin settle -- <FIXTURE>...
+//! ```
+//! The RISC Zero receipts are verified by the Groth16 verifier of RISC Zero, and the SP1
+//! proofs by the mock verifier, see [`ProofVerifier`].
+
+use clap::Parser;
+use input_provider::fixture::ProofFixture;
+use settlement::{
+    ProgramKeys,
+    ProofVerifier,
+    Settlement,
+};
+use std::{
+    fs,
+    path::PathBuf,
+};
+
+/// The arguments for the command.
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    /// The fixtures of the consecutive blocks.
+    #[clap(required = true)]
+    fixtures: Vec<PathBuf>,
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+
+    let fixtures = args
+        .fixtures
+        .iter()
+        .map(|path| {
+            let fixture: ProofFixture = serde_json::from_slice(&fs::read(path)?)?;
+            Ok(fixture)
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let first = fixtures
+        .first()
+        .ok_or_else(|| anyhow::anyhow!("No fixtures to settle"))?;
+    let program_key: [u8; 32] = decode_hex(&first.vkey)?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("The program key must be 32 bytes"))?;
+    anyhow::ensure!(
+        fixtures.iter().all(|fixture| fixture.zkvm == first.zkvm),
+        "The fixtures are proven by different zkVMs"
+    );
+    let verifier = ProofVerifier::for_zkvm(first.zkvm);
+    let keys = ProgramKeys {
+        program_key,
+        ..Default::default()
+    };
+    let mut settlement =
+        Settlement::deploy_before(verifier, keys, &decode_hex(&first.public_values)?)?;
+
+    for fixture in &fixtures {
+        settlement.update_state(
+            &decode_hex(&fixture.public_values)?,
+            &decode_hex(&fixture.proof)?,
+        )?;
+        println!(
+            "Settled the block {} at {} proven by {:?}",
+            hex::encode(fixture.decoded.block_id),
+            fixture.decoded.block_height,
+            fixture.zkvm
+        );
+    }
+
+    println!(
+        "The latest settled block: {} at {}",
+        hex::encode(settlement.block_id()?),
+        settlement.block_height()?
+    );
+    Ok(())
+}
+
+fn decode_hex(value: &str) -> anyhow::Result<Vec<u8>> {
+    Ok(hex::decode(value.trim_start_matches("0x"))?)
+}
@@ -0,0 +1,554 @@
+//! Simulates the settlement of the Fuel chain on L1 without a network.
+//!
+//! The `FuelStateUpdate` contract is executed in the in-process EVM. It verifies the proof
+//! of the block with the verifier contract, decodes the `PublicValuesStruct` and accepts only
+//! the child of the latest settled block, so gaps and replays are rejected. The aggregated
+//! proofs of consecutive blocks are accepted only if they aggregate the proofs of the prover,
+//! see [`ProgramKeys`].
+//!
+//! The RISC Zero receipts are verified by `RiscZeroProofVerifier` with the Groth16 verifier
+//! of RISC Zero, see [`ProofVerifier`]. The SP1 verifier gateway is not a part of the
+//! repository, so the SP1 proofs are accepted by the `MockProofVerifier`.
+//!
+//! The contracts are compiled by Foundry, so the harness is built only with the `forge`
+//! feature.
+#![cfg(feature = "forge")]
+
+use alloy_sol_types::{
+    private::{
+        Address as SolAddress,
+        FixedBytes,
+    },
+    sol,
+    SolCall,
+    SolInterface,
+    SolType,
+    SolValue,
+};
+use input_provider::fixture::Zkvm;
+use prover::{
+    AggregatedPublicValuesStruct,
+    PublicValuesStruct,
+};
+use revm::{
+    db::{
+        CacheDB,
+        EmptyDB,
+    },
+    primitives::{
+        Address,
+        ExecutionResult,
+        Output,
+        TxKind,
+        U256,
+    },
+    Evm,
+};
+
+sol! {
+    contract FuelStateUpdate {
+        error UnexpectedParentBlock(bytes32 expected, bytes32 actual);
+        error UnexpectedBlockHeight(uint32 expected, uint32 actual);
+        error UnexpectedProgram(bytes32 expected, bytes32 actual);
+
+        function updateState(bytes calldata publicValues, bytes calldata proofBytes) external;
+        function updateAggregatedState(
+            bytes calldata publicValues,
+            bytes calldata proofBytes
+        ) external;
+        function blockId() external view returns (bytes32);
+        function blockHeight() external view returns (uint32);
+    }
+}
+
+/// The Foundry artifact of the `FuelStateUpdate` contract.
+const FUEL_STATE_UPDATE: &str =
+    include_str!("../../contracts/out/FuelStateUpdate.sol/FuelStateUpdate.json");
+/// The Foundry artifact of the `MockProofVerifier` contract.
+const MOCK_PROOF_VERIFIER: &str =
+    include_str!("../../contracts/out/MockProofVerifier.sol/MockProofVerifier.json");
+/// The Foundry artifact of the `RiscZeroProofVerifier` contract.
+const RISC_ZERO_PROOF_VERIFIER: &str = include_str!(
+    "../../contracts/out/RiscZeroProofVerifier.sol/RiscZeroProofVerifier.json"
+);
+/// The Foundry artifact of the Groth16 verifier of RISC Zero.
+const RISC_ZERO_GROTH16_VERIFIER: &str =
+    include_str!("../../contracts/out/RiscZeroGroth16.sol/RiscZeroGroth16.json");
+
+/// The account that deploys the contracts and sends the proofs.
+const SENDER: Address = Address::repeat_byte(0x11);
+const GAS_LIMIT: u64 = 30_000_000;
+
+/// The verifier of the proofs deployed with the `FuelStateUpdate` contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProofVerifier {
+    /// `MockProofVerifier`, which accepts any non-empty proof.
+    Mock,
+    /// `RiscZeroProofVerifier` with the Groth16 verifier of RISC Zero,
+    /// which verifies the encoded seals of the RISC Zero receipts.
+    RiscZeroGroth16,
+}
+
+impl ProofVerifier {
+    /// Returns the verifier of the Groth16 proofs generated by the `zkvm`.
+    pub fn for_zkvm(zkvm: Zkvm) -> Self {
+        match zkvm {
+            Zkvm::Risc0 => ProofVerifier::RiscZeroGroth16,
+            Zkvm::Sp1 => ProofVerifier::Mock,
+        }
+    }
+}
+
+/// The keys of the programs whose proofs are accepted by the `FuelStateUpdate` contract.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProgramKeys {
+    /// The verification key hash (SP1) or the image id (RISC Zero) of the prover.
+    pub program_key: [u8; 32],
+    /// The verification key hash (SP1) or the image id (RISC Zero) of the aggregation program.
+    pub aggregation_program_key: [u8; 32],
+    /// The id of the prover committed by the aggregation program, see [`prover::program_id`].
+    /// The aggregation program verifies the block proofs against the program id from its
+    /// input, so the aggregated proofs of other programs are rejected by this id.
+    pub program_id: [u8; 32],
+}
+
+/// The local EVM with the deployed `FuelStateUpdate` contract.
+pub struct Settlement {
+    evm: Evm<'static, (), CacheDB<EmptyDB>>,
+    contract: Address,
+}
+
+impl Settlement {
+    /// Deploys the contract with the `verifier` for the programs with the `keys`,
+    /// settled at the block with the `block_id` and the `block_height`.
+    pub fn deploy(
+        verifier: ProofVerifier,
+        keys: ProgramKeys,
+        block_id: [u8; 32],
+        block_height: u32,
+    ) -> anyhow::Result<Self> {
+        let evm = Evm::builder()
+            .with_db(CacheDB::new(EmptyDB::default()))
+            .build();
+        let mut settlement = Self {
+            evm,
+            contract: Address::ZERO,
+        };
+
+        let verifier = settlement.create_verifier(verifier)?;
+        let constructor_args = (
+            SolAddress::from_slice(verifier.as_slice()),
+            FixedBytes::from(keys.program_key),
+            FixedBytes::from(keys.aggregation_program_key),
+            FixedBytes::from(keys.program_id),
+            FixedBytes::from(block_id),
+            block_height,
+        )
+            .abi_encode_params();
+        settlement.contract = settlement
+            .create([bytecode(FUEL_STATE_UPDATE)?, constructor_args].concat())?;
+        Ok(settlement)
+    }
+
+    /// Deploys the contract settled at the parent of the block with the ABI encoded
+    /// `public_values`, so this block is the next one to settle.
+    pub fn deploy_before(
+        verifier: ProofVerifier,
+        keys: ProgramKeys,
+        public_values: &[u8],
+    ) -> anyhow::Result<Self> {
+        let public_values = PublicValuesStruct::abi_decode(public_values, true)?;
+        let parent_block_height = public_values
+            .block_height
+            .checked_sub(1)
+            .ok_or_else(|| anyhow::anyhow!("The genesis block can't be settled"))?;
+        Self::deploy(
+            verifier,
+            keys,
+            public_values.parent_block_id.to_be_bytes(),
+            parent_block_height,
+        )
+    }
+
+    /// Settles the block with the ABI encoded `public_values` proven by the `proof`.
+    pub fn update_state(
+        &mut self,
+        public_values: &[u8],
+        proof: &[u8],
+    ) -> anyhow::Result<()> {
+        let call = FuelStateUpdate::updateStateCall {
+            publicValues: public_values.to_vec().into(),
+            proofBytes: proof.to_vec().into(),
+        };
+        self.call(call.abi_encode(), true)?;
+        Ok(())
+    }
+
+    /// Settles the consecutive blocks with the ABI encoded aggregated `public_values`
+    /// proven by the `proof` of the aggregation program.
+    pub fn update_aggregated_state(
+        &mut self,
+        public_values: &[u8],
+        proof: &[u8],
+    ) -> anyhow::Result<()> {
+        let call = FuelStateUpdate::updateAggregatedStateCall {
+            publicValues: public_values.to_vec().into(),
+            proofBytes: proof.to_vec().into(),
+        };
+        self.call(call.abi_encode(), true)?;
+        Ok(())
+    }
+
+    /// Returns the id of the latest settled block.
+    pub fn block_id(&mut self) -> anyhow::Result<[u8; 32]> {
+        let output = self.call(FuelStateUpdate::blockIdCall {}.abi_encode(), false)?;
+        let block_id =
+            FuelStateUpdate::blockIdCall::abi_decode_returns(&output, true)?._0;
+        Ok(block_id.0)
+    }
+
+    /// Returns the height of the latest settled block.
+    pub fn block_height(&mut self) -> anyhow::Result<u32> {
+        let output =
+            self.call(FuelStateUpdate::blockHeightCall {}.abi_encode(), false)?;
+        let block_height =
+            FuelStateUpdate::blockHeightCall::abi_decode_returns(&output, true)?._0;
+        Ok(block_height)
+    }
+
+    fn create_verifier(&mut self, verifier: ProofVerifier) -> anyhow::Result<Address> {
+        match verifier {
+            ProofVerifier::Mock => self.create(bytecode(MOCK_PROOF_VERIFIER)?),
+            ProofVerifier::RiscZeroGroth16 => {
+                let groth16 = self.create(bytecode(RISC_ZERO_GROTH16_VERIFIER)?)?;
+                let constructor_args =
+                    (SolAddress::from_slice(groth16.as_slice()),).abi_encode_params();
+                self.create(
+                    [bytecode(RISC_ZERO_PROOF_VERIFIER)?, constructor_args].concat(),
+                )
+            }
+        }
+    }
+
+    fn create(&mut self, code: Vec<u8>) -> anyhow::Result<Address> {
+        match self.transact(TxKind::Create, code, true)? {
+            Output::Create(_, Some(address)) => Ok(address),
+            _ => anyhow::bail!("The contract is not created"),
+        }
+    }
+
+    fn call(&mut self, data: Vec<u8>, commit: bool) -> anyhow::Result<Vec<u8>> {
+        let output = self.transact(TxKind::Call(self.contract), data, commit)?;
+        Ok(output.into_data().to_vec())
+    }
+
+    /// Executes the transaction, and commits its changes if `commit` is set.
+    fn transact(
+        &mut self,
+        kind: TxKind,
+        data: Vec<u8>,
+        commit: bool,
+    ) -> anyhow::Result<Output> {
+        let tx = self.evm.tx_mut();
+        tx.caller = SENDER;
+        tx.transact_to = kind;
+        tx.data = data.into();
+        tx.value = U256::ZERO;
+        tx.gas_limit = GAS_LIMIT;
+
+        let result = if commit {
+            self.evm.transact_commit()
+        } else {
+            self.evm.transact().map(|result| result.result)
+        }
+        .map_err(|e| anyhow::anyhow!("The EVM failed: {e:?}"))?;
+
+        match result {
+            ExecutionResult::Success { output, .. } => Ok(output),
+            ExecutionResult::Revert { output, .. } => {
+                Err(anyhow::anyhow!(revert_reason(&output)))
+            }
+            ExecutionResult::Halt { reason, .. } => {
+                anyhow::bail!("The transaction halted: {reason:?}")
+            }
+        }
+    }
+}
+
+/// Returns the deployment bytecode from the Foundry `artifact`.
+fn bytecode(artifact: &str) -> anyhow::Result<Vec<u8>> {
+    let artifact: serde_json::Value = serde_json::from_str(artifact)?;
+    let bytecode = artifact["bytecode"]["object"]
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("The artifact has no bytecode"))?;
+    Ok(hex::decode(bytecode.trim_start_matches("0x"))?)
+}
+
+/// Describes why the `FuelStateUpdate` contract rejected the block.
+fn revert_reason(output: &[u8]) -> String {
+    use FuelStateUpdate::FuelStateUpdateErrors;
+
+    match FuelStateUpdateErrors::abi_decode(output, true) {
+        Ok(FuelStateUpdateErrors::UnexpectedParentBlock(error)) => format!(
+            "The block is not a child of the latest settled block {}, its parent is {}",
+            error.expected, error.actual
+        ),
+        Ok(FuelStateUpdateErrors::UnexpectedBlockHeight(error)) => format!(
+            "The block at {} doesn't follow the latest settled block, expected {}",
+            error.actual, error.expected
+        ),
+        Ok(FuelStateUpdateErrors::UnexpectedProgram(error)) => format!(
+            "The aggregated proofs are proofs of the program {}, expected {}",
+            error.actual, error.expected
+        ),
+        Err(_) => format!("The transaction reverted: 0x{}", hex::encode(output)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use input_provider::{
+        fixture::ProofFixture,
+        logs::init_logging,
+        start_node_with_workloads_and_produce_prover_inputs,
+        workload::Workload,
+        NodeConfig,
+    };
+
+    const PROOF: &[u8] = &[1];
+    /// The directory with the Groth16 fixtures of consecutive blocks, exported by the RISC Zero
+    /// host with `cargo run --release -- fixture <DIR> --fixtures-dir <THIS_DIR>`.
+    const RISC0_FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures");
+
+    /// Returns the ABI encoded public values of the `blocks` consecutive blocks.
+    async fn prove_blocks(blocks: usize) -> Vec<Vec<u8>> {
+        let service = start_node_with_workloads_and_produce_prover_inputs(
+            NodeConfig::default(),
+            &vec![Workload::Script; blocks],
+        )
+        .await
+        .unwrap();
+
+        service
+            .inputs
+            .iter()
+            .map(|input| {
+                let input = bincode::serialize(input).unwrap();
+                PublicValuesStruct::abi_encode(&prover::prove(&input).unwrap())
+            })
+            .collect()
+    }
+
+    /// Returns the RISC Zero fixtures from [`RISC0_FIXTURES`] in the order of the blocks.
+    fn risc0_fixtures() -> Vec<ProofFixture> {
+        let mut fixtures: Vec<ProofFixture> = std::fs::read_dir(RISC0_FIXTURES)
+            .unwrap_or_else(|e| panic!("Unable to read {RISC0_FIXTURES}: {e}"))
+            .map(|entry| {
+                serde_json::from_slice(&std::fs::read(entry.unwrap().path()).unwrap())
+                    .unwrap()
+            })
+            .filter(|fixture: &ProofFixture| fixture.zkvm == Zkvm::Risc0)
+            .collect();
+        fixtures.sort_by_key(|fixture| fixture.decoded.block_height);
+        fixtures
+    }
+
+    fn decode_hex(value: &str) -> Vec<u8> {
+        hex::decode(value.trim_start_matches("0x")).unwrap()
+    }
+
+    #[tokio::test]
+    async fn settlement_accepts_consecutive_blocks() {
+        init_logging();
+        let public_values = prove_blocks(3).await;
+        let mut settlement = Settlement::deploy_before(
+            ProofVerifier::Mock,
+            ProgramKeys::default(),
+            &public_values[0],
+        )
+        .unwrap();
+
+        for values in &public_values {
+            settlement.update_state(values, PROOF).unwrap();
+        }
+
+        let last = PublicValuesStruct::abi_decode(&public_values[2], true).unwrap();
+        assert_eq!(settlement.block_id().unwrap(), last.block_id.to_be_bytes());
+        assert_eq!(settlement.block_height().unwrap(), last.block_height);
+    }
+
+    #[tokio::test]
+    async fn settlement_rejects_replays() {
+        init_logging();
+        let public_values = prove_blocks(2).await;
+        let mut settlement = Settlement::deploy_before(
+            ProofVerifier::Mock,
+            ProgramKeys::default(),
+            &public_values[0],
+        )
+        .unwrap();
+
+        settlement.update_state(&public_values[0], PROOF).unwrap();
+        settlement.update_state(&public_values[1], PROOF).unwrap();
+        let result = settlement.update_state(&public_values[1], PROOF);
+
+        let error = result.unwrap_err().to_string();
+        assert!(error.contains("is not a child"), "{error}");
+        let last = PublicValuesStruct::abi_decode(&public_values[1], true).unwrap();
+        assert_eq!(settlement.block_height().unwrap(), last.block_height);
+    }
+
+    #[tokio::test]
+    async fn settlement_rejects_gaps() {
+        init_logging();
+        let public_values = prove_blocks(2).await;
+        let mut settlement = Settlement::deploy_before(
+            ProofVerifier::Mock,
+            ProgramKeys::default(),
+            &public_values[0],
+        )
+        .unwrap();
+
+        let result = settlement.update_state(&public_values[1], PROOF);
+
+        let error = result.unwrap_err().to_string();
+        assert!(error.contains("is not a child"), "{error}");
+        let first = PublicValuesStruct::abi_decode(&public_values[0], true).unwrap();
+        assert_eq!(
+            settlement.block_id().unwrap(),
+            first.parent_block_id.to_be_bytes()
+        );
+    }
+
+    #[tokio::test]
+    async fn settlement_rejects_empty_proofs() {
+        init_logging();
+        let public_values = prove_blocks(1).await;
+        let mut settlement = Settlement::deploy_before(
+            ProofVerifier::Mock,
+            ProgramKeys::default(),
+            &public_values[0],
+        )
+        .unwrap();
+
+        assert!(settlement.update_state(&public_values[0], &[]).is_err());
+    }
+
+    #[tokio::test]
+    async fn settlement_accepts_aggregated_blocks() {
+        init_logging();
+        let public_values = prove_blocks(3).await;
+        let keys = ProgramKeys {
+            program_id: prover::program_id([1; 8]),
+            ..Default::default()
+        };
+        let mut settlement =
+            Settlement::deploy_before(ProofVerifier::Mock, keys, &public_values[0])
+                .unwrap();
+
+        let aggregated = prover::aggregate(keys.program_id, &public_values).unwrap();
+        settlement
+            .update_aggregated_state(
+                &AggregatedPublicValuesStruct::abi_encode(&aggregated),
+                PROOF,
+            )
+            .unwrap();
+
+        assert_eq!(
+            settlement.block_id().unwrap(),
+            aggregated.block_id.to_be_bytes()
+        );
+        assert_eq!(
+            settlement.block_height().unwrap(),
+            aggregated.last_block_height
+        );
+    }
+
+    #[tokio::test]
+    async fn settlement_rejects_aggregated_proofs_of_other_programs() {
+        init_logging();
+        let public_values = prove_blocks(2).await;
+        let keys = ProgramKeys {
+            program_id: prover::program_id([1; 8]),
+            ..Default::default()
+        };
+        let mut settlement =
+            Settlement::deploy_before(ProofVerifier::Mock, keys, &public_values[0])
+                .unwrap();
+
+        // The aggregation program verified the proofs of another program from its input.
+        let aggregated =
+            prover::aggregate(prover::program_id([2; 8]), &public_values).unwrap();
+        let result = settlement.update_aggregated_state(
+            &AggregatedPublicValuesStruct::abi_encode(&aggregated),
+            PROOF,
+        );
+
+        let error = result.unwrap_err().to_string();
+        assert!(error.contains("proofs of the program"), "{error}");
+        let first = PublicValuesStruct::abi_decode(&public_values[0], true).unwrap();
+        assert_eq!(
+            settlement.block_id().unwrap(),
+            first.parent_block_id.to_be_bytes()
+        );
+    }
+
+    #[tokio::test]
+    async fn risc0_verifier_rejects_invalid_seals() {
+        init_logging();
+        let public_values = prove_blocks(1).await;
+        let mut settlement = Settlement::deploy_before(
+            ProofVerifier::RiscZeroGroth16,
+            ProgramKeys::default(),
+            &public_values[0],
+        )
+        .unwrap();
+
+        assert!(settlement.update_state(&public_values[0], PROOF).is_err());
+        let first = PublicValuesStruct::abi_decode(&public_values[0], true).unwrap();
+        assert_eq!(
+            settlement.block_id().unwrap(),
+            first.parent_block_id.to_be_bytes()
+        );
+    }
+
+    #[test]
+    #[ignore = "needs the Groth16 fixtures exported by the RISC Zero host into `fixtures`"]
+    fn risc0_verifier_accepts_receipts_of_the_host() {
+        init_logging();
+        let fixtures = risc0_fixtures();
+        let first = fixtures
+            .first()
+            .unwrap_or_else(|| panic!("No RISC Zero fixtures in {RISC0_FIXTURES}"));
+        let image_id = decode_hex(&first.vkey).try_into().unwrap();
+        let mut settlement = Settlement::deploy_before(
+            ProofVerifier::RiscZeroGroth16,
+            ProgramKeys {
+                program_key: image_id,
+                ..Default::default()
+            },
+            &decode_hex(&first.public_values),
+        )
+        .unwrap();
+
+        for fixture in &fixtures {
+            let public_values = decode_hex(&fixture.public_values);
+            let seal = decode_hex(&fixture.proof);
+
+            // The seal proves only the journal it was generated for.
+            let mut tampered = public_values.clone();
+            *tampered.last_mut().unwrap() ^= 1;
+            assert!(settlement.update_state(&tampered, &seal).is_err());
+
+            settlement.update_state(&public_values, &seal).unwrap();
+        }
+
+        let last = fixtures.last().unwrap();
+        assert_eq!(settlement.block_id().unwrap(), last.decoded.block_id);
+        assert_eq!(
+            settlement.block_height().unwrap(),
+            last.decoded.block_height
+        );
+    }
+}